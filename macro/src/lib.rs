@@ -1,4 +1,6 @@
-use macro_utils::{extract_expr_to_str, extract_expr_to_u64, RpcData, RPC_DATA};
+use macro_utils::{
+    extract_expr_to_bool, extract_expr_to_str, extract_expr_to_u64, is_replay, RpcData, RPC_DATA,
+};
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{
@@ -28,6 +30,8 @@ struct MacroDataRequire {
     pub block_min: u64,
     pub block_max: u64,
     pub spec_version: Option<String>,
+    /// The test sends raw HTTP requests, which are not recorded in cassettes
+    pub live: bool,
     pub err: Result<(), Path>,
 }
 
@@ -39,6 +43,7 @@ impl Parse for MacroDataRequire {
             block_min: 0,
             block_max: u64::MAX,
             spec_version: None,
+            live: false,
             err: Ok(()),
         };
 
@@ -62,6 +67,9 @@ impl Parse for MacroDataRequire {
                             Err(_) => None,
                         }
                     }
+                    "live" => {
+                        parsed_params.live = extract_expr_to_bool(&arg.value).unwrap_or(false);
+                    }
                     _ => {
                         parsed_params.err = Err(arg.path);
                     }
@@ -80,6 +88,7 @@ impl MacroDataRequire {
             block_min,
             block_max,
             spec_version,
+            live: _,
             err: _,
         } = self;
 
@@ -94,6 +103,15 @@ pub fn require(args: TokenStream, item: TokenStream) -> TokenStream {
     let block_data = RPC_DATA.clone();
     let macro_data = parse_macro_input!(args as MacroDataRequire);
 
+    if macro_data.live && is_replay() {
+        let mut func = parse_macro_input!(item as ItemFn);
+        func.attrs.push(
+            parse_quote!(#[ignore = "Test sends raw HTTP requests, which cassettes cannot replay"]),
+        );
+
+        return quote!(#func).into();
+    }
+
     if macro_data.should_ignore(block_data) {
         item
     } else {
@@ -1,8 +1,8 @@
 use anyhow::anyhow;
 use lazy_static::lazy_static;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
-use std::{fs::File, io::Read};
+use std::{fs, fs::File, io::Read};
 use syn::{Expr, Lit};
 use tokio::runtime;
 use url::Url;
@@ -27,7 +27,12 @@ impl TestConfig {
     }
 }
 
-#[derive(Clone, Debug)]
+/// Environment variable set to `record` or `replay` to run the tests against cassettes
+pub const CASSETTE_ENV: &str = "DITTO_CASSETTE";
+/// Directory of the cassettes, relative to the workspace root where macros are expanded
+const CASSETTE_DIR: &str = "./unit_tests/cassettes";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RpcData {
     pub latest_chain_block: u64,
     pub block_number: u64,
@@ -38,7 +43,44 @@ lazy_static! {
     pub static ref RPC_DATA: RpcData = get_rpc_data();
 }
 
+///
+/// Node state the tests are filtered on. When replaying cassettes, it is the state saved
+/// when they were recorded so that the same tests run.
+///
 fn get_rpc_data() -> RpcData {
+    let path = format!("{CASSETTE_DIR}/rpc_data.json");
+
+    match cassette_mode().as_deref() {
+        Some("replay") => {
+            let content = fs::read_to_string(&path)
+                .expect("Cassettes must be recorded before they are replayed");
+            serde_json::from_str(&content).expect("Invalid cassette rpc data")
+        }
+        Some("record") => {
+            let data = query_rpc_data();
+            fs::create_dir_all(CASSETTE_DIR).expect("Failed to create cassette dir");
+            fs::write(&path, serde_json::to_string_pretty(&data).unwrap())
+                .expect("Failed to save cassette rpc data");
+            data
+        }
+        _ => query_rpc_data(),
+    }
+}
+
+///
+/// Cassette mode set by `DITTO_CASSETTE`. The `unit_tests` build script makes cargo rebuild
+/// the tests when it changes.
+///
+fn cassette_mode() -> Option<String> {
+    std::env::var(CASSETTE_ENV).ok()
+}
+
+/// Whether tests are compiled to replay cassettes, without network access
+pub fn is_replay() -> bool {
+    cassette_mode().as_deref() == Some("replay")
+}
+
+fn query_rpc_data() -> RpcData {
     let config =
         TestConfig::new("./secret.json").expect("'./secret.json' must contain correct node urls");
     let deoxys = JsonRpcClient::new(HttpTransport::new(
@@ -82,3 +124,13 @@ pub fn extract_expr_to_u64(expr: &Expr) -> anyhow::Result<u64> {
         _ => Err(anyhow!("Not a literal expression")),
     }
 }
+
+pub fn extract_expr_to_bool(expr: &Expr) -> anyhow::Result<bool> {
+    match expr {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Bool(lit_bool) => anyhow::Ok(lit_bool.value),
+            _ => Err(anyhow!("Not a boolean literal")),
+        },
+        _ => Err(anyhow!("Not a literal expression")),
+    }
+}
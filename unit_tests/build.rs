@@ -0,0 +1,5 @@
+fn main() {
+    // `#[require]` reads the cassette mode when tests are compiled, so that switching
+    // modes rebuilds them with the matching node state
+    println!("cargo:rerun-if-env-changed=DITTO_CASSETTE");
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+#
+# Downloads the Starknet OpenRPC specs used to validate node responses (see `src/spec.rs`).
+# Specs are pinned to the starknet-specs release tag of each supported version.
+#
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+VERSIONS=("0.5.1" "0.6.0")
+FILES=("starknet_api_openrpc.json" "starknet_trace_api_openrpc.json" "starknet_write_api.json")
+
+for version in "${VERSIONS[@]}"; do
+    mkdir -p "$version"
+    for file in "${FILES[@]}"; do
+        curl -sSfL "https://raw.githubusercontent.com/starkware-libs/starknet-specs/v$version/api/$file" \
+            -o "$version/$file"
+    done
+done
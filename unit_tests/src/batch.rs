@@ -0,0 +1,135 @@
+use anyhow::Context;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::transport::{RawError, RawResponse};
+
+///
+/// A single call of a JSON-RPC batch, with params as they are sent over the wire.
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub method: String,
+    pub params: Value,
+}
+
+impl Request {
+    pub fn new(method: &str, params: Value) -> Self {
+        Self {
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    /// JSON-RPC envelope of the request
+    pub fn to_json(&self, id: u64) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": self.method,
+            "params": self.params,
+        })
+    }
+}
+
+///
+/// JSON-RPC client over raw HTTP, as starknet-rs only sends one call per request.
+///
+#[derive(Debug, Clone)]
+pub struct BatchClient {
+    http: reqwest::Client,
+    url: Url,
+}
+
+impl BatchClient {
+    pub fn new(url: Url) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    ///
+    /// Posts `body` as is and returns the node's json response, whatever the HTTP status,
+    /// so that malformed requests can be sent as well.
+    ///
+    pub async fn post(&self, body: &Value) -> anyhow::Result<Value> {
+        let (status, text) = self.send(body).await?;
+
+        serde_json::from_str(&text)
+            .with_context(|| format!("Expected a json response, got {status}: {text}"))
+    }
+
+    ///
+    /// Posts `body` and returns the HTTP status and text of the response. Only fails on
+    /// transport errors, such as a node which cannot be reached.
+    ///
+    pub async fn send(&self, body: &Value) -> anyhow::Result<(StatusCode, String)> {
+        let response = self.http.post(self.url.clone()).json(body).send().await?;
+        let status = response.status();
+
+        Ok((status, response.text().await?))
+    }
+
+    /// Sends `request` on its own, outside of a batch
+    pub async fn call(&self, request: &Request) -> anyhow::Result<RawResponse> {
+        let response = self.post(&request.to_json(0)).await?;
+        serde_json::from_value(response.clone())
+            .with_context(|| format!("Invalid JSON-RPC response {response}"))
+    }
+
+    ///
+    /// Sends `requests` as a single batch, with their index as id. Responses are returned
+    /// in the order of the requests.
+    ///
+    pub async fn batch(&self, requests: &[Request]) -> anyhow::Result<Vec<RawResponse>> {
+        let body = requests
+            .iter()
+            .enumerate()
+            .map(|(id, request)| request.to_json(id as u64))
+            .collect::<Vec<_>>();
+
+        match_responses(requests.len(), self.post(&Value::Array(body)).await?)
+    }
+}
+
+///
+/// Matches the responses of a batch to its `len` requests by id, as nodes may answer in
+/// any order. Fails if the node rejected the whole batch, or if a response is missing,
+/// duplicated or does not match any request.
+///
+pub fn match_responses(len: usize, body: Value) -> anyhow::Result<Vec<RawResponse>> {
+    let entries = match body {
+        Value::Array(entries) => entries,
+        Value::Object(mut object) if object.contains_key("error") => {
+            let error: RawError = serde_json::from_value(object.remove("error").unwrap())?;
+            anyhow::bail!("Batch rejected: {} {}", error.code, error.message);
+        }
+        body => anyhow::bail!("Expected an array of responses, got {body}"),
+    };
+
+    let mut responses = vec![None; len];
+    for entry in entries {
+        let id = entry
+            .get("id")
+            .and_then(Value::as_u64)
+            .with_context(|| format!("Invalid id in {entry}"))?;
+        let slot = responses
+            .get_mut(id as usize)
+            .with_context(|| format!("Response to unknown request {id}"))?;
+        anyhow::ensure!(slot.is_none(), "Duplicate response to request {id}");
+
+        *slot = Some(
+            serde_json::from_value(entry.clone())
+                .with_context(|| format!("Invalid JSON-RPC response {entry}"))?,
+        );
+    }
+
+    responses
+        .into_iter()
+        .enumerate()
+        .map(|(id, response)| response.with_context(|| format!("No response to request {id}")))
+        .collect()
+}
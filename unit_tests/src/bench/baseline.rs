@@ -0,0 +1,285 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use starknet_providers::Provider;
+
+use super::{
+    latency::{LatencyReport, LatencyWorkload},
+    Method,
+};
+
+/// One-sided z-score above which a latency increase is significant, p < 0.01
+const SIGNIFICANCE_Z: f64 = 2.326;
+
+///
+/// Machine the benchmark ran on, as latencies are only comparable on the same setup.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Environment {
+    pub host: String,
+    pub os: String,
+    pub arch: String,
+    pub cpus: usize,
+    pub ditto_version: String,
+}
+
+impl Environment {
+    pub fn current() -> Self {
+        Self {
+            host: std::env::var("HOSTNAME").unwrap_or_default(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: std::thread::available_parallelism().map_or(0, usize::from),
+            ditto_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+///
+/// Results of a latency benchmark run, saved to be compared with later runs.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Unix timestamp of the run
+    pub timestamp: u64,
+    pub environment: Environment,
+    /// `specVersion` of each node
+    pub versions: BTreeMap<String, String>,
+    pub workload: LatencyWorkload,
+    /// Blocks the requests were spread over
+    pub blocks: Vec<u64>,
+    pub report: LatencyReport,
+}
+
+impl Baseline {
+    pub async fn new<P: Provider>(
+        nodes: &[(&str, &P)],
+        workload: LatencyWorkload,
+        blocks: Vec<u64>,
+        report: LatencyReport,
+    ) -> anyhow::Result<Self> {
+        let mut versions = BTreeMap::new();
+        for (node, provider) in nodes.iter() {
+            versions.insert(node.to_string(), provider.spec_version().await?);
+        }
+
+        Ok(Self {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            environment: Environment::current(),
+            versions,
+            workload,
+            blocks,
+            report,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("Invalid baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Could not write {}", path.display()))
+    }
+}
+
+///
+/// Thresholds above which a difference between two runs is a regression.
+///
+#[derive(Debug, Clone)]
+pub struct RegressionOptions {
+    /// Fraction by which p50 or p99 latency may grow
+    pub latency: f64,
+    /// Fraction by which throughput may drop
+    pub throughput: f64,
+    /// Points by which the error rate may grow
+    pub error_rate: f64,
+}
+
+impl Default for RegressionOptions {
+    fn default() -> Self {
+        Self {
+            latency: 0.1,
+            throughput: 0.1,
+            error_rate: 0.01,
+        }
+    }
+}
+
+///
+/// A metric of a method on a node which got worse since the baseline.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub method: Method,
+    pub node: String,
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    /// Mann-Whitney z-score of the latency distributions, `None` for the error rate
+    pub z: Option<f64>,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {}: {} went from {:.2} to {:.2}",
+            self.method.name(),
+            self.node,
+            self.metric,
+            self.baseline,
+            self.current
+        )?;
+        if self.baseline > 0.0 {
+            write!(
+                f,
+                " ({:+.1}%)",
+                (self.current / self.baseline - 1.0) * 100.0
+            )?;
+        }
+        if let Some(z) = self.z {
+            write!(f, ", z = {z:.1}")?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Mann-Whitney U test of two latency histograms, as a z-score: positive if `current`
+/// tends to be slower than `baseline`. Ties are not corrected for, which only makes the
+/// test more conservative.
+///
+pub fn mann_whitney(baseline: &Histogram<u64>, current: &Histogram<u64>) -> f64 {
+    let (n1, n2) = (baseline.len() as f64, current.len() as f64);
+    if n1 == 0.0 || n2 == 0.0 {
+        return 0.0;
+    }
+
+    let baseline = baseline
+        .iter_recorded()
+        .map(|value| (value.value_iterated_to(), value.count_at_value() as f64))
+        .collect::<Vec<_>>();
+
+    // number of baseline latencies below each current latency, counting ties as half
+    let mut u = 0.0;
+    let mut below = 0.0;
+    let mut i = 0;
+    for value in current.iter_recorded() {
+        let latency = value.value_iterated_to();
+        while i < baseline.len() && baseline[i].0 < latency {
+            below += baseline[i].1;
+            i += 1;
+        }
+        let ties = match baseline.get(i) {
+            Some((equal, count)) if *equal == latency => *count,
+            _ => 0.0,
+        };
+        u += value.count_at_value() as f64 * (below + ties / 2.0);
+    }
+
+    let mean = n1 * n2 / 2.0;
+    let deviation = (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt();
+    (u - mean) / deviation
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+///
+/// Compares every method and node measured in both runs. Latency and throughput
+/// regressions must be both significant and larger than the threshold, so that noise
+/// between runs is not reported.
+///
+pub fn compare(
+    baseline: &LatencyReport,
+    current: &LatencyReport,
+    options: &RegressionOptions,
+) -> Vec<Regression> {
+    let mut regressions = vec![];
+
+    for (method, nodes) in current.results.iter() {
+        for (node, stats) in nodes.iter() {
+            let Some(reference) = baseline.get(*method, node) else {
+                continue;
+            };
+            let regression = |metric, baseline, current, z| Regression {
+                method: *method,
+                node: node.clone(),
+                metric,
+                baseline,
+                current,
+                z,
+            };
+
+            let z = mann_whitney(&reference.histogram, &stats.histogram);
+            for (metric, quantile) in [("p50 (ms)", 0.5), ("p99 (ms)", 0.99)] {
+                let (before, after) = (
+                    millis(reference.quantile(quantile)),
+                    millis(stats.quantile(quantile)),
+                );
+                if z > SIGNIFICANCE_Z && after > before * (1.0 + options.latency) {
+                    regressions.push(regression(metric, before, after, Some(z)));
+                }
+            }
+
+            // a single throughput per run can not be tested on its own, but at a fixed
+            // concurrency it only drops if requests got slower
+            let (before, after) = (reference.throughput(), stats.throughput());
+            if z > SIGNIFICANCE_Z && after < before * (1.0 - options.throughput) {
+                regressions.push(regression("throughput (req/s)", before, after, Some(z)));
+            }
+
+            let (before, after) = (reference.error_rate(), stats.error_rate());
+            if after > before + options.error_rate {
+                regressions.push(regression("error rate", before, after, None));
+            }
+        }
+    }
+
+    regressions
+}
+
+///
+/// Comparison of a run with a baseline, with warnings if they are not comparable.
+///
+pub fn compare_baselines(
+    baseline: &Baseline,
+    current: &Baseline,
+    options: &RegressionOptions,
+) -> (Vec<String>, Vec<Regression>) {
+    let mut warnings = vec![];
+    if baseline.environment != current.environment {
+        warnings.push(format!(
+            "Environments differ: {:?} vs {:?}",
+            baseline.environment, current.environment
+        ));
+    }
+    if baseline.workload != current.workload || baseline.blocks != current.blocks {
+        warnings.push(String::from("Workloads differ"));
+    }
+    for (node, version) in current.versions.iter() {
+        match baseline.versions.get(node) {
+            Some(before) if before != version => warnings.push(format!(
+                "{node} spec version went from {before} to {version}"
+            )),
+            _ => {}
+        }
+    }
+
+    (
+        warnings,
+        compare(&baseline.report, &current.report, options),
+    )
+}
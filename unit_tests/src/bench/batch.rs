@@ -0,0 +1,242 @@
+use std::{collections::BTreeMap, fmt};
+
+use futures::{stream, StreamExt};
+use serde_json::{json, Value};
+use starknet_core::types::FieldElement;
+use tokio::time::Instant;
+
+use super::{Method, Stats, Targets};
+use crate::{
+    batch::{BatchClient, Request},
+    transport::RawResponse,
+};
+
+fn felt(value: &FieldElement) -> Value {
+    Value::String(format!("0x{value:x}"))
+}
+
+///
+/// The `i`-th request of `method` as raw JSON-RPC, with the same parameters as
+/// [super::execute].
+///
+pub fn request(method: Method, targets: &Targets, i: usize) -> Request {
+    let block_id = json!({ "block_number": targets.blocks[i % targets.blocks.len()] });
+    let probe = &targets.probe;
+
+    let params = match method {
+        Method::BlockNumber => json!([]),
+        Method::GetBlockWithTxHashes
+        | Method::GetBlockWithTxs
+        | Method::GetStateUpdate
+        | Method::TraceBlockTransactions => json!({ "block_id": block_id }),
+        Method::GetStorageAt => json!({
+            "contract_address": felt(&probe.contract_address),
+            "key": felt(&probe.storage_key),
+            "block_id": block_id,
+        }),
+        Method::GetNonce | Method::GetClassHashAt => json!({
+            "block_id": block_id,
+            "contract_address": felt(&probe.contract_address),
+        }),
+        Method::Call => json!({
+            "request": {
+                "contract_address": felt(&probe.call.contract_address),
+                "entry_point_selector": felt(&probe.call.entry_point_selector),
+                "calldata": probe.call.calldata.iter().map(felt).collect::<Vec<_>>(),
+            },
+            "block_id": block_id,
+        }),
+        Method::GetEvents => json!({
+            "filter": { "from_block": block_id, "to_block": block_id, "chunk_size": 100 },
+        }),
+        Method::GetTransactionReceipt => json!({
+            "transaction_hash": felt(&targets.transactions[i % targets.transactions.len()]),
+        }),
+    };
+
+    Request::new(method.name(), params)
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchWorkload {
+    pub method: Method,
+    /// Calls issued for each batch size
+    pub requests: usize,
+    /// Calls per batch, calls are also sent one per HTTP request for reference
+    pub batch_sizes: Vec<usize>,
+    /// HTTP requests in flight at the same time
+    pub concurrency: usize,
+}
+
+impl Default for BatchWorkload {
+    fn default() -> Self {
+        Self {
+            method: Method::GetStorageAt,
+            requests: 1000,
+            batch_sizes: vec![10, 50, 100],
+            concurrency: 1,
+        }
+    }
+}
+
+///
+/// Calls sent one per HTTP request if `batch_size` is `None`, or in batches. Stats are
+/// those of HTTP requests.
+///
+#[derive(Debug, Clone)]
+pub struct BatchRun {
+    pub batch_size: Option<usize>,
+    pub calls: u64,
+    /// Calls which got an error, or whose HTTP request failed
+    pub failed: u64,
+    pub stats: Stats,
+}
+
+impl BatchRun {
+    pub fn calls_per_second(&self) -> f64 {
+        match self.stats.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.calls.saturating_sub(self.failed) as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+///
+/// Issues `workload.requests` calls to a single node, in batches of `batch_size` or
+/// individually.
+///
+pub async fn measure_batch(
+    client: &BatchClient,
+    targets: &Targets,
+    workload: &BatchWorkload,
+    batch_size: Option<usize>,
+) -> BatchRun {
+    let requests = (0..workload.requests)
+        .map(|i| request(workload.method, targets, i))
+        .collect::<Vec<_>>();
+    let chunks = requests.chunks(batch_size.unwrap_or(1).max(1));
+
+    let start = Instant::now();
+    let results = stream::iter(chunks)
+        .map(|chunk| async move {
+            let sent = Instant::now();
+            let failed = match batch_size {
+                None => match client.call(&chunk[0]).await {
+                    Ok(RawResponse::Success { .. }) => Ok(0),
+                    Ok(RawResponse::Error { .. }) => Ok(1),
+                    Err(e) => Err(e),
+                },
+                Some(_) => client.batch(chunk).await.map(|responses| {
+                    responses
+                        .iter()
+                        .filter(|response| matches!(response, RawResponse::Error { .. }))
+                        .count()
+                }),
+            };
+            (chunk.len(), sent.elapsed(), failed)
+        })
+        .buffer_unordered(workload.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut run = BatchRun {
+        batch_size,
+        calls: workload.requests as u64,
+        failed: 0,
+        stats: Stats::new(),
+    };
+    for (calls, latency, failed) in results {
+        match failed {
+            Ok(failed) => {
+                run.stats.record(latency);
+                run.failed += failed as u64;
+            }
+            Err(e) => {
+                log::debug!("{e:#}");
+                run.stats.record_error();
+                run.failed += calls as u64;
+            }
+        }
+    }
+    run.stats.elapsed = start.elapsed();
+
+    run
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchReport {
+    pub method: Method,
+    /// The targets had no parameter for the method, which was not benchmarked
+    pub skipped: bool,
+    pub runs: BTreeMap<String, Vec<BatchRun>>,
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.skipped {
+            return writeln!(
+                f,
+                "{}: skipped, no target to call it on",
+                self.method.name()
+            );
+        }
+
+        writeln!(f, "{}:", self.method.name())?;
+        for (node, runs) in self.runs.iter() {
+            writeln!(f, "{node}:")?;
+            writeln!(f, "{:>10} {:>10} {}", "batch", "calls/s", Stats::header())?;
+            for run in runs.iter() {
+                let batch = match run.batch_size {
+                    Some(size) => size.to_string(),
+                    None => String::from("none"),
+                };
+                writeln!(
+                    f,
+                    "{batch:>10} {:>10.1} {}",
+                    run.calls_per_second(),
+                    run.stats
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Sends the same calls individually then in batches of each size, on each node in turn.
+///
+pub async fn run_batch(
+    nodes: &[(&str, &BatchClient)],
+    targets: &Targets,
+    workload: &BatchWorkload,
+) -> BatchReport {
+    let mut report = BatchReport {
+        method: workload.method,
+        skipped: !targets.supports(workload.method),
+        runs: BTreeMap::new(),
+    };
+    if report.skipped {
+        log::warn!(
+            "Skipping {}, no target to call it on",
+            workload.method.name()
+        );
+        return report;
+    }
+
+    let batch_sizes = std::iter::once(None).chain(workload.batch_sizes.iter().copied().map(Some));
+
+    for (node, client) in nodes.iter() {
+        let mut runs = vec![];
+        for batch_size in batch_sizes.clone() {
+            let batch = match batch_size {
+                Some(size) => format!("in batches of {size}"),
+                None => String::from("individually"),
+            };
+            log::info!("Sending {} calls {batch} to {node}", workload.requests);
+            runs.push(measure_batch(client, targets, workload, batch_size).await);
+        }
+        report.runs.insert(node.to_string(), runs);
+    }
+
+    report
+}
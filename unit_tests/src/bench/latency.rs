@@ -0,0 +1,139 @@
+use std::{collections::BTreeMap, fmt};
+
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use starknet_providers::Provider;
+use tokio::time::Instant;
+
+use super::{execute, Method, Stats, Targets};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyWorkload {
+    pub methods: Vec<Method>,
+    /// Requests per method and node
+    pub requests: usize,
+    /// Requests in flight at the same time
+    pub concurrency: usize,
+    /// Requests per method and node issued before measuring, to warm up caches
+    pub warmup: usize,
+}
+
+impl Default for LatencyWorkload {
+    fn default() -> Self {
+        Self {
+            methods: Method::ALL.to_vec(),
+            requests: 100,
+            concurrency: 1,
+            warmup: 10,
+        }
+    }
+}
+
+///
+/// Latency of each method on each node, for the same workload.
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencyReport {
+    pub results: BTreeMap<Method, BTreeMap<String, Stats>>,
+    /// Methods of the workload the targets had no parameter for, which were not benchmarked
+    #[serde(default)]
+    pub skipped: Vec<Method>,
+}
+
+impl LatencyReport {
+    pub fn get(&self, method: Method, node: &str) -> Option<&Stats> {
+        self.results.get(&method)?.get(node)
+    }
+}
+
+impl fmt::Display for LatencyReport {
+    /// One line per node under each method, so that nodes are read side by side
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<32} {:<12} {}", "method", "node", Stats::header())?;
+        for (method, nodes) in self.results.iter() {
+            for (i, (node, stats)) in nodes.iter().enumerate() {
+                let method = if i == 0 { method.name() } else { "" };
+                writeln!(f, "{method:<32} {node:<12} {stats}")?;
+            }
+        }
+        for method in self.skipped.iter() {
+            writeln!(f, "{:<32} skipped, no target to call it on", method.name())?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Issues `workload.requests` requests of a single method to a single node, at most
+/// `workload.concurrency` at a time.
+///
+pub async fn measure<P: Provider>(
+    provider: &P,
+    method: Method,
+    targets: &Targets,
+    workload: &LatencyWorkload,
+) -> Stats {
+    let timed = |i: usize| async move {
+        let start = Instant::now();
+        let result = execute(provider, method, targets, i).await;
+        (start.elapsed(), result)
+    };
+
+    stream::iter(0..workload.warmup)
+        .map(timed)
+        .buffer_unordered(workload.concurrency.max(1))
+        .for_each(|_| async {})
+        .await;
+
+    let start = Instant::now();
+    let mut stats = Stats::new();
+    let mut requests = stream::iter(0..workload.requests)
+        .map(timed)
+        .buffer_unordered(workload.concurrency.max(1));
+
+    while let Some((latency, result)) = requests.next().await {
+        match result {
+            Ok(()) => stats.record(latency),
+            Err(e) => {
+                log::debug!("{}: {e}", method.name());
+                stats.record_error();
+            }
+        }
+    }
+    stats.elapsed = start.elapsed();
+
+    stats
+}
+
+///
+/// Measures every method of the workload on each node in turn, so that nodes do not
+/// compete for the benchmarking machine's resources.
+///
+pub async fn run_latency<P: Provider>(
+    nodes: &[(&str, &P)],
+    targets: &Targets,
+    workload: &LatencyWorkload,
+) -> LatencyReport {
+    let mut report = LatencyReport::default();
+
+    for method in workload.methods.iter().copied() {
+        if !targets.supports(method) {
+            log::warn!("Skipping {}, no target to call it on", method.name());
+            report.skipped.push(method);
+            continue;
+        }
+
+        for (node, provider) in nodes.iter() {
+            log::info!("Benchmarking {} on {node}", method.name());
+            let stats = measure(*provider, method, targets, workload).await;
+
+            report
+                .results
+                .entry(method)
+                .or_default()
+                .insert(node.to_string(), stats);
+        }
+    }
+
+    report
+}
@@ -0,0 +1,326 @@
+use std::{collections::BTreeMap, fmt, str::FromStr, time::Duration};
+
+use anyhow::{bail, Context};
+use futures::{stream::FuturesUnordered, StreamExt};
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    rngs::StdRng,
+    SeedableRng,
+};
+use starknet_providers::Provider;
+use tokio::time::{sleep_until, Instant};
+
+use super::{execute, format_duration, Method, Stats, Targets};
+
+/// A step is saturated if its throughput is below this fraction of the target rate
+const SATURATION_THROUGHPUT: f64 = 0.9;
+/// A step is saturated if its p99 is this many times the p99 of the first step
+const SATURATION_LATENCY: u32 = 10;
+/// A step is saturated if more requests than this fail
+const SATURATION_ERRORS: f64 = 0.05;
+
+///
+/// Share of each method in the generated requests, ex: `get_storage_at=60,call=20,get_events=20`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mix(pub Vec<(Method, f64)>);
+
+impl Default for Mix {
+    fn default() -> Self {
+        Self(vec![
+            (Method::GetStorageAt, 60.0),
+            (Method::Call, 20.0),
+            (Method::GetEvents, 20.0),
+        ])
+    }
+}
+
+impl fmt::Display for Mix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mix = self
+            .0
+            .iter()
+            .map(|(method, weight)| format!("{method}={weight}"))
+            .collect::<Vec<_>>();
+        write!(f, "{}", mix.join(","))
+    }
+}
+
+impl FromStr for Mix {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mix = s
+            .split(',')
+            .map(|entry| {
+                let (method, weight) = entry
+                    .split_once('=')
+                    .with_context(|| format!("Expected <method>=<weight>, got '{entry}'"))?;
+                let weight = weight
+                    .parse::<f64>()
+                    .with_context(|| format!("Invalid weight '{weight}'"))?;
+                Ok((method.parse()?, weight))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self(mix))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Target request rate of each step, in requests per second
+    pub rates: Vec<f64>,
+    pub step_duration: Duration,
+    pub mix: Mix,
+    /// Requests in flight above which new requests are delayed, the delay still being
+    /// counted in their latency
+    pub max_in_flight: usize,
+    pub seed: u64,
+}
+
+impl LoadOptions {
+    /// A single step at `rate` requests per second
+    pub fn fixed(rate: f64, duration: Duration) -> Self {
+        Self {
+            rates: vec![rate],
+            step_duration: duration,
+            mix: Mix::default(),
+            max_in_flight: 1000,
+            seed: 0,
+        }
+    }
+
+    /// Steps from `from` up to `to` requests per second, increasing by `step`
+    pub fn ramp(from: f64, to: f64, step: f64, step_duration: Duration) -> Self {
+        let rates = (0..)
+            .map(|i| from + step * i as f64)
+            .take_while(|rate| *rate <= to)
+            .collect();
+
+        Self {
+            rates,
+            ..Self::fixed(from, step_duration)
+        }
+    }
+}
+
+///
+/// Requests scheduled during one step of the load. Latencies are measured from the time
+/// each request was scheduled to be sent, so that the time spent waiting behind slow
+/// requests is not hidden (coordinated omission), while service times are measured from
+/// the time it was actually sent.
+///
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub target_rate: f64,
+    pub latency: Stats,
+    pub service: Stats,
+}
+
+impl StepResult {
+    fn new(target_rate: f64) -> Self {
+        Self {
+            target_rate,
+            latency: Stats::new(),
+            service: Stats::new(),
+        }
+    }
+
+    /// Successful requests per second
+    pub fn achieved_rate(&self) -> f64 {
+        match self.latency.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => (self.latency.count() - self.latency.errors) as f64 / secs,
+            _ => 0.0,
+        }
+    }
+
+    ///
+    /// The node could not keep up with the target rate: throughput fell behind, latency
+    /// exploded compared to `reference_p99`, or requests failed.
+    ///
+    pub fn is_saturated(&self, reference_p99: Duration) -> bool {
+        self.achieved_rate() < SATURATION_THROUGHPUT * self.target_rate
+            || self.latency.quantile(0.99) > reference_p99 * SATURATION_LATENCY
+            || self.latency.error_rate() > SATURATION_ERRORS
+    }
+}
+
+///
+/// Target rate of the first saturated step, `None` if the node kept up with every step.
+///
+pub fn saturation(steps: &[StepResult]) -> Option<f64> {
+    let reference_p99 = steps.first()?.latency.quantile(0.99);
+
+    steps
+        .iter()
+        .find(|step| step.is_saturated(reference_p99))
+        .map(|step| step.target_rate)
+}
+
+///
+/// Issues requests to a single node at the rates of `options`, whether or not previous
+/// requests were answered.
+///
+pub async fn generate<P: Provider>(
+    provider: &P,
+    targets: &Targets,
+    options: &LoadOptions,
+) -> anyhow::Result<Vec<StepResult>> {
+    anyhow::ensure!(
+        options.rates.iter().all(|rate| *rate > 0.0),
+        "Request rates must be positive, got {:?}",
+        options.rates
+    );
+    if let Some((method, _)) = options
+        .mix
+        .0
+        .iter()
+        .find(|(method, weight)| *weight > 0.0 && !targets.supports(*method))
+    {
+        bail!("No target to call {} on", method.name());
+    }
+    let weights = WeightedIndex::new(options.mix.0.iter().map(|(_, weight)| *weight))
+        .with_context(|| format!("Invalid mix '{}'", options.mix))?;
+    let mut rng = StdRng::seed_from_u64(options.seed);
+
+    let mut steps = options
+        .rates
+        .iter()
+        .map(|rate| StepResult::new(*rate))
+        .collect::<Vec<_>>();
+    let mut ends = vec![Instant::now(); steps.len()];
+    let mut in_flight = FuturesUnordered::new();
+    let mut record = |(step, intended, sent, result): (usize, Instant, Instant, Result<(), _>)| {
+        let now = Instant::now();
+        let (latency, service) = (now - intended, now - sent);
+        match result {
+            Ok(()) => {
+                steps[step].latency.record(latency);
+                steps[step].service.record(service);
+            }
+            Err(e) => {
+                log::debug!("{e}");
+                steps[step].latency.record_error();
+                steps[step].service.record_error();
+            }
+        }
+        ends[step] = ends[step].max(now);
+    };
+
+    let start = Instant::now();
+    let mut request = 0;
+
+    for (step, rate) in options.rates.iter().enumerate() {
+        log::info!("Generating {rate} requests/s");
+
+        let step_start = start + options.step_duration * step as u32;
+        let step_end = step_start + options.step_duration;
+        let interval = Duration::from_secs_f64(1.0 / rate);
+
+        for n in 0.. {
+            let intended = step_start + interval.mul_f64(n as f64);
+            if intended >= step_end {
+                break;
+            }
+
+            loop {
+                tokio::select! {
+                    _ = sleep_until(intended) => break,
+                    Some(done) = in_flight.next(), if !in_flight.is_empty() => record(done),
+                }
+            }
+            while in_flight.len() >= options.max_in_flight.max(1) {
+                if let Some(done) = in_flight.next().await {
+                    record(done);
+                }
+            }
+
+            let method = options.mix.0[weights.sample(&mut rng)].0;
+            let i = request;
+            request += 1;
+            in_flight.push(async move {
+                let sent = Instant::now();
+                let result = execute(provider, method, targets, i).await;
+                (step, intended, sent, result)
+            });
+        }
+    }
+
+    while let Some(done) = in_flight.next().await {
+        record(done);
+    }
+
+    for (step, result) in steps.iter_mut().enumerate() {
+        let step_start = start + options.step_duration * step as u32;
+        let elapsed = (ends[step] - step_start).max(options.step_duration);
+        result.latency.elapsed = elapsed;
+        result.service.elapsed = elapsed;
+    }
+
+    Ok(steps)
+}
+
+///
+/// Steps of the load on each node, and the rate at which each node saturated.
+///
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    pub steps: BTreeMap<String, Vec<StepResult>>,
+}
+
+impl LoadReport {
+    pub fn saturation(&self, node: &str) -> Option<f64> {
+        saturation(self.steps.get(node)?)
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (node, steps) in self.steps.iter() {
+            writeln!(f, "{node}:")?;
+            writeln!(
+                f,
+                "{:>8} {:>10} {:>12} {}",
+                "target",
+                "achieved",
+                "service p99",
+                Stats::header()
+            )?;
+            for step in steps.iter() {
+                writeln!(
+                    f,
+                    "{:>6.1}/s {:>8.1}/s {:>12} {}",
+                    step.target_rate,
+                    step.achieved_rate(),
+                    format_duration(step.service.quantile(0.99)),
+                    step.latency
+                )?;
+            }
+            match self.saturation(node) {
+                Some(rate) => writeln!(f, "{node} saturated at {rate} requests/s")?,
+                None => writeln!(f, "{node} did not saturate")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Generates the same load on each node in turn.
+///
+pub async fn run_load<P: Provider>(
+    nodes: &[(&str, &P)],
+    targets: &Targets,
+    options: &LoadOptions,
+) -> anyhow::Result<LoadReport> {
+    let mut report = LoadReport::default();
+
+    for (node, provider) in nodes.iter() {
+        log::info!("Generating load on {node}");
+        let steps = generate(*provider, targets, options).await?;
+        report.steps.insert(node.to_string(), steps);
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,369 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::bail;
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use starknet_core::types::{BlockId, EventFilter, FieldElement, MaybePendingBlockWithTxHashes};
+use starknet_providers::{Provider, ProviderError};
+
+use crate::equivalence::Probe;
+
+/// Blocks searched for transactions before the targets, when the targets have none
+const TRANSACTION_SCAN: u64 = 100;
+
+pub mod baseline;
+pub mod batch;
+pub mod latency;
+pub mod load;
+pub mod pagination;
+pub mod state_age;
+pub mod sync;
+
+///
+/// RPC method issued by the benchmarks, with parameters taken from [Targets].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Method {
+    BlockNumber,
+    GetBlockWithTxHashes,
+    GetBlockWithTxs,
+    GetStateUpdate,
+    GetStorageAt,
+    GetNonce,
+    GetClassHashAt,
+    Call,
+    GetEvents,
+    GetTransactionReceipt,
+    TraceBlockTransactions,
+}
+
+impl Method {
+    pub const ALL: [Self; 11] = [
+        Self::BlockNumber,
+        Self::GetBlockWithTxHashes,
+        Self::GetBlockWithTxs,
+        Self::GetStateUpdate,
+        Self::GetStorageAt,
+        Self::GetNonce,
+        Self::GetClassHashAt,
+        Self::Call,
+        Self::GetEvents,
+        Self::GetTransactionReceipt,
+        Self::TraceBlockTransactions,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BlockNumber => "starknet_blockNumber",
+            Self::GetBlockWithTxHashes => "starknet_getBlockWithTxHashes",
+            Self::GetBlockWithTxs => "starknet_getBlockWithTxs",
+            Self::GetStateUpdate => "starknet_getStateUpdate",
+            Self::GetStorageAt => "starknet_getStorageAt",
+            Self::GetNonce => "starknet_getNonce",
+            Self::GetClassHashAt => "starknet_getClassHashAt",
+            Self::Call => "starknet_call",
+            Self::GetEvents => "starknet_getEvents",
+            Self::GetTransactionReceipt => "starknet_getTransactionReceipt",
+            Self::TraceBlockTransactions => "starknet_traceBlockTransactions",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => write!(f, "{name}"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+impl FromStr for Method {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL
+            .into_iter()
+            .find(|method| method.to_string() == s || method.name() == s)
+        {
+            Some(method) => Ok(method),
+            None => bail!(
+                "Unknown method '{s}', expected one of {}",
+                Self::ALL.map(|method| method.to_string()).join(", ")
+            ),
+        }
+    }
+}
+
+///
+/// Blocks and transactions the benchmarked methods are called on, fetched once so that
+/// every node is queried with the same parameters.
+///
+#[derive(Debug, Clone)]
+pub struct Targets {
+    pub blocks: Vec<u64>,
+    pub transactions: Vec<FieldElement>,
+    pub probe: Probe,
+}
+
+impl Targets {
+    ///
+    /// Targets on `blocks`, with the transactions of those blocks. If they have none, the
+    /// transactions of the closest earlier block which has some are used instead, so that
+    /// receipts can still be benchmarked.
+    ///
+    pub async fn fetch<P: Provider>(provider: &P, blocks: Vec<u64>) -> anyhow::Result<Self> {
+        anyhow::ensure!(!blocks.is_empty(), "No block to benchmark");
+
+        let mut transactions = vec![];
+        for block_number in blocks.iter() {
+            transactions.extend(block_transactions(provider, *block_number).await?);
+        }
+
+        let lowest = blocks.iter().copied().min().unwrap_or_default();
+        for block_number in (lowest.saturating_sub(TRANSACTION_SCAN)..lowest).rev() {
+            if !transactions.is_empty() {
+                break;
+            }
+            transactions = block_transactions(provider, block_number).await?;
+        }
+        if transactions.is_empty() {
+            log::warn!("No transaction found in or before the target blocks");
+        }
+
+        Ok(Self {
+            blocks,
+            transactions,
+            probe: Probe::default(),
+        })
+    }
+
+    /// Whether the targets hold the parameters `method` needs
+    pub fn supports(&self, method: Method) -> bool {
+        method != Method::GetTransactionReceipt || !self.transactions.is_empty()
+    }
+
+    fn block(&self, i: usize) -> BlockId {
+        BlockId::Number(self.blocks[i % self.blocks.len()])
+    }
+}
+
+async fn block_transactions<P: Provider>(
+    provider: &P,
+    block_number: u64,
+) -> anyhow::Result<Vec<FieldElement>> {
+    match provider
+        .get_block_with_tx_hashes(BlockId::Number(block_number))
+        .await?
+    {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok(block.transactions),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+            bail!("Expected block {block_number}, got pending block")
+        }
+    }
+}
+
+///
+/// Issues the `i`-th request of `method`, cycling through the targets. Responses are
+/// deserialized but dropped. Methods the targets do not [Targets::supports] must be
+/// skipped by the caller.
+///
+pub async fn execute<P: Provider>(
+    provider: &P,
+    method: Method,
+    targets: &Targets,
+    i: usize,
+) -> Result<(), ProviderError> {
+    let block_id = targets.block(i);
+    let probe = &targets.probe;
+
+    match method {
+        Method::BlockNumber => provider.block_number().await.map(drop),
+        Method::GetBlockWithTxHashes => provider.get_block_with_tx_hashes(block_id).await.map(drop),
+        Method::GetBlockWithTxs => provider.get_block_with_txs(block_id).await.map(drop),
+        Method::GetStateUpdate => provider.get_state_update(block_id).await.map(drop),
+        Method::GetStorageAt => provider
+            .get_storage_at(probe.contract_address, probe.storage_key, block_id)
+            .await
+            .map(drop),
+        Method::GetNonce => provider
+            .get_nonce(block_id, probe.contract_address)
+            .await
+            .map(drop),
+        Method::GetClassHashAt => provider
+            .get_class_hash_at(block_id, probe.contract_address)
+            .await
+            .map(drop),
+        Method::Call => provider.call(probe.call.clone(), block_id).await.map(drop),
+        Method::GetEvents => provider
+            .get_events(
+                EventFilter {
+                    from_block: Some(block_id),
+                    to_block: Some(block_id),
+                    address: None,
+                    keys: None,
+                },
+                None,
+                100,
+            )
+            .await
+            .map(drop),
+        Method::GetTransactionReceipt => provider
+            .get_transaction_receipt(targets.transactions[i % targets.transactions.len()])
+            .await
+            .map(drop),
+        Method::TraceBlockTransactions => {
+            provider.trace_block_transactions(block_id).await.map(drop)
+        }
+    }
+}
+
+///
+/// Latencies of the successful requests of a series, recorded in an HDR histogram with a
+/// microsecond resolution, and the number of failed requests. Failures are counted but not
+/// timed, as fast failures would pull the percentiles down.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "StatsRecord", try_from = "StatsRecord")]
+pub struct Stats {
+    /// Latencies of successful requests
+    pub histogram: Histogram<u64>,
+    pub errors: u64,
+    /// Wall clock time taken by all requests
+    pub elapsed: Duration,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            histogram: Histogram::new(3).expect("3 significant digits are supported"),
+            errors: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Header matching the columns of the Display of [Stats]
+    pub fn header() -> String {
+        format!(
+            "{:>7} {:>10} {:>10} {:>10} {:>10} {:>7} {:>10}",
+            "count", "p50", "p90", "p99", "max", "errors", "throughput"
+        )
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        self.histogram.saturating_record(latency.as_micros() as u64);
+    }
+
+    pub fn record_error(&mut self) {
+        self.errors += 1;
+    }
+
+    /// Requests recorded, successful or not
+    pub fn count(&self) -> u64 {
+        self.histogram.len() + self.errors
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        match self.count() {
+            0 => 0.0,
+            count => self.errors as f64 / count as f64,
+        }
+    }
+
+    /// Requests per second
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.count() as f64 / secs,
+            _ => 0.0,
+        }
+    }
+
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        Duration::from_micros(self.histogram.value_at_quantile(quantile))
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.histogram.max())
+    }
+
+    pub fn mean(&self) -> Duration {
+        Duration::from_micros(self.histogram.mean() as u64)
+    }
+
+    pub fn merge(&mut self, other: &Stats) {
+        self.histogram
+            .add(&other.histogram)
+            .expect("Histograms auto resize");
+        self.errors += other.errors;
+        self.elapsed += other.elapsed;
+    }
+}
+
+/// [Stats] as json, with the histogram as its recorded `[latency_us, count]` pairs
+#[derive(Serialize, Deserialize)]
+struct StatsRecord {
+    errors: u64,
+    elapsed_secs: f64,
+    latencies_us: Vec<(u64, u64)>,
+}
+
+impl From<Stats> for StatsRecord {
+    fn from(stats: Stats) -> Self {
+        Self {
+            errors: stats.errors,
+            elapsed_secs: stats.elapsed.as_secs_f64(),
+            latencies_us: stats
+                .histogram
+                .iter_recorded()
+                .map(|value| (value.value_iterated_to(), value.count_at_value()))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<StatsRecord> for Stats {
+    type Error = hdrhistogram::RecordError;
+
+    fn try_from(record: StatsRecord) -> Result<Self, Self::Error> {
+        let mut stats = Stats {
+            errors: record.errors,
+            elapsed: Duration::from_secs_f64(record.elapsed_secs),
+            ..Default::default()
+        };
+        for (latency, count) in record.latencies_us {
+            stats.histogram.record_n(latency, count)?;
+        }
+        Ok(stats)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>7} {:>10} {:>10} {:>10} {:>10} {:>6.2}% {:>8.1}/s",
+            self.count(),
+            format_duration(self.quantile(0.5)),
+            format_duration(self.quantile(0.9)),
+            format_duration(self.quantile(0.99)),
+            format_duration(self.max()),
+            self.error_rate() * 100.0,
+            self.throughput(),
+        )
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    match duration.as_micros() {
+        micros if micros < 1_000 => format!("{micros}us"),
+        micros if micros < 1_000_000 => format!("{:.2}ms", micros as f64 / 1_000.0),
+        micros => format!("{:.2}s", micros as f64 / 1_000_000.0),
+    }
+}
@@ -0,0 +1,283 @@
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use starknet_core::{types::FieldElement, utils::get_selector_from_name};
+use starknet_providers::{Provider, ProviderError};
+use tokio::time::Instant;
+
+use super::{format_duration, Stats};
+use crate::{
+    constants::STARKGATE_ETH_CONTRACT_ADDR,
+    events::{get_events, EventQuery},
+};
+
+///
+/// Address and keys events are filtered on, named for the report.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventsFilter {
+    pub name: String,
+    pub address: Option<FieldElement>,
+    pub keys: Option<Vec<Vec<FieldElement>>>,
+}
+
+impl EventsFilter {
+    ///
+    /// No filter, an address filter, a key filter and both, on StarkGate ETH `Transfer`
+    /// events which are emitted throughout the chain's history.
+    ///
+    pub fn defaults() -> Vec<Self> {
+        let address = FieldElement::from_hex_be(STARKGATE_ETH_CONTRACT_ADDR).unwrap();
+        let keys = vec![vec![get_selector_from_name("Transfer").unwrap()]];
+
+        vec![
+            Self {
+                name: String::from("none"),
+                address: None,
+                keys: None,
+            },
+            Self {
+                name: String::from("address"),
+                address: Some(address),
+                keys: None,
+            },
+            Self {
+                name: String::from("keys"),
+                address: None,
+                keys: Some(keys.clone()),
+            },
+            Self {
+                name: String::from("address+keys"),
+                address: Some(address),
+                keys: Some(keys),
+            },
+        ]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PaginationWorkload {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub chunk_sizes: Vec<u64>,
+    pub filters: Vec<EventsFilter>,
+    /// Pages after which a drain stops, so that unfiltered queries over large ranges
+    /// end in reasonable time
+    pub max_pages: Option<usize>,
+}
+
+impl PaginationWorkload {
+    pub fn new(from_block: u64, to_block: u64) -> Self {
+        Self {
+            from_block,
+            to_block,
+            chunk_sizes: vec![10, 100, 1000],
+            filters: EventsFilter::defaults(),
+            max_pages: None,
+        }
+    }
+
+    /// Every combination of filter and chunk size
+    pub fn queries(&self) -> Vec<(&str, EventQuery)> {
+        self.filters
+            .iter()
+            .flat_map(|filter| {
+                self.chunk_sizes.iter().map(|chunk_size| {
+                    (
+                        filter.name.as_str(),
+                        EventQuery {
+                            from_block: self.from_block,
+                            to_block: self.to_block,
+                            address: filter.address,
+                            keys: filter.keys.clone(),
+                            chunk_size: *chunk_size,
+                        },
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+///
+/// Pages of a query read by following its continuation tokens.
+///
+#[derive(Debug, Clone, Default)]
+pub struct Drain {
+    pub events: u64,
+    /// Latency of each page
+    pub pages: Stats,
+    pub time_to_first_page: Duration,
+    /// `false` if the drain stopped at `max_pages` while there were pages left
+    pub complete: bool,
+}
+
+impl Drain {
+    pub fn events_per_second(&self) -> f64 {
+        match self.pages.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.events as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+///
+/// Reads every page of `query`, one after the other as each needs the continuation token
+/// of the previous one.
+///
+pub async fn drain<P: Provider>(
+    provider: &P,
+    query: &EventQuery,
+    max_pages: Option<usize>,
+) -> Result<Drain, ProviderError> {
+    let start = Instant::now();
+    let mut drain = Drain::default();
+    let mut continuation_token = None;
+
+    loop {
+        if max_pages.is_some_and(|max| drain.pages.count() as usize >= max) {
+            break;
+        }
+
+        let sent = Instant::now();
+        let page = get_events(provider, query, continuation_token).await?;
+        drain.pages.record(sent.elapsed());
+        if drain.pages.count() == 1 {
+            drain.time_to_first_page = start.elapsed();
+        }
+
+        drain.events += page.events.len() as u64;
+        continuation_token = page.continuation_token;
+        if continuation_token.is_none() {
+            drain.complete = true;
+            break;
+        }
+    }
+
+    drain.pages.elapsed = start.elapsed();
+    Ok(drain)
+}
+
+///
+/// Drains of the same query on each node.
+///
+#[derive(Debug, Clone)]
+pub struct PaginationResult {
+    pub filter: String,
+    pub chunk_size: u64,
+    pub drains: BTreeMap<String, Drain>,
+}
+
+impl PaginationResult {
+    /// Whether complete drains found a different number of events on different nodes
+    pub fn is_mismatch(&self) -> bool {
+        let mut counts = self
+            .drains
+            .values()
+            .filter(|drain| drain.complete)
+            .map(|drain| drain.events);
+        match counts.next() {
+            Some(first) => counts.any(|events| events != first),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PaginationReport {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub results: Vec<PaginationResult>,
+}
+
+impl fmt::Display for PaginationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Events of blocks {} to {}:",
+            self.from_block, self.to_block
+        )?;
+
+        let nodes = self
+            .results
+            .first()
+            .map(|result| result.drains.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        write!(f, "{:>12} {:>6}", "filter", "chunk")?;
+        for node in nodes.iter() {
+            write!(
+                f,
+                " {:>44}",
+                format!("{node} events/pages/first page/events/s")
+            )?;
+        }
+        writeln!(f)?;
+
+        for result in self.results.iter() {
+            write!(f, "{:>12} {:>6}", result.filter, result.chunk_size)?;
+            for drain in result.drains.values() {
+                let events = match drain.complete {
+                    true => drain.events.to_string(),
+                    false => format!("{}+", drain.events),
+                };
+                write!(
+                    f,
+                    " {:>44}",
+                    format!(
+                        "{events}/{}/{}/{:.1}",
+                        drain.pages.count(),
+                        format_duration(drain.time_to_first_page),
+                        drain.events_per_second()
+                    )
+                )?;
+            }
+            if result.is_mismatch() {
+                write!(f, " event counts differ")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Drains every combination of filter and chunk size on each node in turn.
+///
+pub async fn run_pagination<P: Provider>(
+    nodes: &[(&str, &P)],
+    workload: &PaginationWorkload,
+) -> anyhow::Result<PaginationReport> {
+    anyhow::ensure!(
+        workload.from_block <= workload.to_block,
+        "Invalid block range {} to {}",
+        workload.from_block,
+        workload.to_block
+    );
+
+    let mut report = PaginationReport {
+        from_block: workload.from_block,
+        to_block: workload.to_block,
+        results: vec![],
+    };
+
+    for (filter, query) in workload.queries() {
+        let mut drains = BTreeMap::new();
+        for (node, provider) in nodes.iter() {
+            log::info!(
+                "Draining events filtered on {filter} by chunks of {} on {node}",
+                query.chunk_size
+            );
+            drains.insert(
+                node.to_string(),
+                drain(*provider, &query, workload.max_pages).await?,
+            );
+        }
+
+        report.results.push(PaginationResult {
+            filter: filter.to_string(),
+            chunk_size: query.chunk_size,
+            drains,
+        });
+    }
+
+    Ok(report)
+}
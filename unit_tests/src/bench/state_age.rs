@@ -0,0 +1,223 @@
+use std::{collections::BTreeMap, fmt};
+
+use starknet_core::types::{BlockId, FieldElement, StarknetError};
+use starknet_providers::{Provider, ProviderError};
+
+use super::{
+    format_duration,
+    latency::{measure, LatencyWorkload},
+    Method, Stats, Targets,
+};
+use crate::equivalence::Probe;
+
+/// A bucket is a cliff if its p50 is this many times the p50 of the next more recent bucket
+const CLIFF_FACTOR: f64 = 2.0;
+
+#[derive(Debug, Clone)]
+pub struct AgeWorkload {
+    pub methods: Vec<Method>,
+    /// Number of block ages, evenly spread from the first block with the probed contract
+    /// up to the tip
+    pub buckets: usize,
+    /// Requests per method, node and age, each on a different block so that they do not
+    /// all hit the same cache entry
+    pub requests: usize,
+    pub probe: Probe,
+}
+
+impl Default for AgeWorkload {
+    fn default() -> Self {
+        Self {
+            methods: vec![Method::GetStorageAt, Method::GetNonce, Method::Call],
+            buckets: 10,
+            requests: 20,
+            probe: Probe::default(),
+        }
+    }
+}
+
+///
+/// Latency of state queries by block age, for each method and node.
+///
+#[derive(Debug, Clone, Default)]
+pub struct AgeReport {
+    pub tip: u64,
+    /// Most recent block of each bucket, from oldest to most recent
+    pub blocks: Vec<u64>,
+    /// Stats of each bucket, in the order of `blocks`
+    pub results: BTreeMap<Method, BTreeMap<String, Vec<Stats>>>,
+}
+
+impl AgeReport {
+    ///
+    /// Blocks at which latency jumps compared to more recent blocks, ex: where the node
+    /// stops keeping state in memory.
+    ///
+    pub fn cliffs(&self, method: Method, node: &str) -> Vec<u64> {
+        let Some(stats) = self.results.get(&method).and_then(|nodes| nodes.get(node)) else {
+            return vec![];
+        };
+
+        stats
+            .windows(2)
+            .zip(self.blocks.iter())
+            .filter(|(pair, _)| {
+                let (older, recent) = (pair[0].quantile(0.5), pair[1].quantile(0.5));
+                older.as_secs_f64() > recent.as_secs_f64() * CLIFF_FACTOR
+            })
+            .map(|(_, block)| *block)
+            .collect()
+    }
+}
+
+impl fmt::Display for AgeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (method, nodes) in self.results.iter() {
+            writeln!(f, "{}:", method.name())?;
+
+            write!(f, "{:>10} {:>10}", "age", "block")?;
+            for node in nodes.keys() {
+                write!(f, " {:>21}", format!("{node} p50/p99"))?;
+            }
+            writeln!(f)?;
+
+            for (i, block) in self.blocks.iter().enumerate() {
+                write!(f, "{:>10} {:>10}", self.tip - block, block)?;
+                for stats in nodes.values() {
+                    let stats = &stats[i];
+                    let latency = format!(
+                        "{}/{}",
+                        format_duration(stats.quantile(0.5)),
+                        format_duration(stats.quantile(0.99))
+                    );
+                    let errors = match stats.errors {
+                        0 => String::new(),
+                        errors => format!(" ({errors} errors)"),
+                    };
+                    write!(f, " {latency:>21}{errors}")?;
+                }
+                writeln!(f)?;
+            }
+
+            for node in nodes.keys() {
+                let cliffs = self.cliffs(*method, node);
+                if !cliffs.is_empty() {
+                    writeln!(f, "{node} latency cliffs at blocks {cliffs:?}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// First block at which `contract` is deployed, by binary search.
+///
+pub async fn deployment_block<P: Provider>(
+    provider: &P,
+    contract: FieldElement,
+    tip: u64,
+) -> anyhow::Result<u64> {
+    let deployed = |block_number| async move {
+        match provider
+            .get_class_hash_at(BlockId::Number(block_number), contract)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    };
+
+    anyhow::ensure!(
+        deployed(tip).await?,
+        "Contract 0x{contract:x} is not deployed at block {tip}"
+    );
+
+    let (mut low, mut high) = (0, tip);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if deployed(middle).await? {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+
+    Ok(low)
+}
+
+///
+/// `buckets` blocks evenly spread over `from..=tip`, from oldest to most recent.
+///
+pub fn age_buckets(from: u64, tip: u64, buckets: usize) -> Vec<u64> {
+    match buckets {
+        0 => vec![],
+        1 => vec![tip],
+        _ => {
+            let mut blocks = (0..buckets as u64)
+                .map(|i| from + (tip - from) * i / (buckets as u64 - 1))
+                .collect::<Vec<_>>();
+            blocks.dedup();
+            blocks
+        }
+    }
+}
+
+///
+/// Queries the same contract state at block ages spread from its deployment up to the
+/// tip, on each node in turn.
+///
+pub async fn run_state_age<P: Provider>(
+    nodes: &[(&str, &P)],
+    workload: &AgeWorkload,
+) -> anyhow::Result<AgeReport> {
+    let Some((_, reference)) = nodes.first().copied() else {
+        anyhow::bail!("No node to benchmark");
+    };
+
+    let mut tip = u64::MAX;
+    for (_, provider) in nodes.iter() {
+        tip = tip.min(provider.block_number().await?);
+    }
+    let from = deployment_block(reference, workload.probe.contract_address, tip).await?;
+    let blocks = age_buckets(from, tip, workload.buckets);
+
+    let mut report = AgeReport {
+        tip,
+        blocks: blocks.clone(),
+        ..Default::default()
+    };
+    // blocks below each bucket's block, one per request
+    let window = (workload.requests as u64).saturating_sub(1);
+    let latency = LatencyWorkload {
+        methods: workload.methods.clone(),
+        requests: workload.requests,
+        concurrency: 1,
+        warmup: 0,
+    };
+
+    for method in workload.methods.iter().copied() {
+        for (node, provider) in nodes.iter() {
+            log::info!("Benchmarking {} by block age on {node}", method.name());
+
+            let mut buckets = vec![];
+            for block in blocks.iter() {
+                let targets = Targets {
+                    blocks: (block.saturating_sub(window).max(from)..=*block).collect(),
+                    transactions: vec![],
+                    probe: workload.probe.clone(),
+                };
+                buckets.push(measure(*provider, method, &targets, &latency).await);
+            }
+
+            report
+                .results
+                .entry(method)
+                .or_default()
+                .insert(node.to_string(), buckets);
+        }
+    }
+
+    Ok(report)
+}
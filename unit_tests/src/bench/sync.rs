@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, fmt, ops::RangeInclusive, time::Duration};
+
+use futures::{future::join_all, stream, StreamExt};
+use starknet_core::types::{BlockId, SyncStatusType};
+use starknet_providers::Provider;
+use tokio::time::{sleep_until, Instant};
+
+use super::format_duration;
+
+/// Blocks whose transactions are counted concurrently
+const COUNT_CONCURRENCY: usize = 16;
+
+///
+/// State of a node at one point of a [sample_sync] run.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncSample {
+    /// Time since the first sample
+    pub elapsed: Duration,
+    /// `starknet_blockNumber`
+    pub block_number: u64,
+    /// Highest block known to the node according to `starknet_syncing`, its block number
+    /// if it is not syncing
+    pub highest_block: u64,
+    /// Transactions in the blocks synced since the first sample
+    pub transactions: u64,
+}
+
+///
+/// Sync progress of a node over time.
+///
+#[derive(Debug, Clone, Default)]
+pub struct SyncProgress {
+    pub samples: Vec<SyncSample>,
+}
+
+impl SyncProgress {
+    fn rate(&self, value: impl Fn(&SyncSample) -> u64) -> f64 {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) if last.elapsed > first.elapsed => {
+                value(last).saturating_sub(value(first)) as f64
+                    / (last.elapsed - first.elapsed).as_secs_f64()
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn blocks_per_second(&self) -> f64 {
+        self.rate(|sample| sample.block_number)
+    }
+
+    pub fn transactions_per_second(&self) -> f64 {
+        self.rate(|sample| sample.transactions)
+    }
+
+    /// Rate at which new blocks were produced by the chain during the run
+    pub fn chain_blocks_per_second(&self) -> f64 {
+        self.rate(|sample| sample.highest_block)
+    }
+
+    ///
+    /// Time left until the node reaches the tip at its current speed, given that the tip
+    /// keeps moving. `None` if the node is not catching up.
+    ///
+    pub fn time_to_tip(&self) -> Option<Duration> {
+        let last = self.samples.last()?;
+        let remaining = last.highest_block.saturating_sub(last.block_number);
+        if remaining == 0 {
+            return Some(Duration::ZERO);
+        }
+
+        let speed = self.blocks_per_second() - self.chain_blocks_per_second();
+        (speed > 0.0).then(|| Duration::from_secs_f64(remaining as f64 / speed))
+    }
+}
+
+impl fmt::Display for SyncProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>10} {:>10} {:>10} {:>10} {:>10}",
+            "elapsed", "block", "highest", "blocks/s", "txs/s"
+        )?;
+        for (i, sample) in self.samples.iter().enumerate() {
+            let window = SyncProgress {
+                samples: self.samples[i.saturating_sub(1)..=i].to_vec(),
+            };
+            writeln!(
+                f,
+                "{:>10} {:>10} {:>10} {:>10.2} {:>10.2}",
+                format!("{:.0}s", sample.elapsed.as_secs_f64()),
+                sample.block_number,
+                sample.highest_block,
+                window.blocks_per_second(),
+                window.transactions_per_second()
+            )?;
+        }
+
+        write!(
+            f,
+            "{:.2} blocks/s, {:.2} txs/s, time to tip: ",
+            self.blocks_per_second(),
+            self.transactions_per_second()
+        )?;
+        match self.time_to_tip() {
+            Some(duration) => writeln!(f, "{}", format_duration(duration)),
+            None => writeln!(f, "never at this speed"),
+        }
+    }
+}
+
+async fn highest_block<P: Provider>(provider: &P, block_number: u64) -> anyhow::Result<u64> {
+    Ok(match provider.syncing().await? {
+        SyncStatusType::Syncing(status) => status.highest_block_num.max(block_number),
+        SyncStatusType::NotSyncing => block_number,
+    })
+}
+
+async fn count_transactions<P: Provider>(
+    provider: &P,
+    blocks: RangeInclusive<u64>,
+) -> anyhow::Result<u64> {
+    let counts = stream::iter(blocks)
+        .map(|block_number| provider.get_block_transaction_count(BlockId::Number(block_number)))
+        .buffered(COUNT_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(counts.into_iter().sum::<Result<u64, _>>()?)
+}
+
+///
+/// Samples `starknet_blockNumber` and `starknet_syncing` every `interval` for `duration`.
+/// Transactions of the synced blocks are counted if `with_transactions` is set, which
+/// costs one request per synced block.
+///
+pub async fn sample_sync<P: Provider>(
+    provider: &P,
+    interval: Duration,
+    duration: Duration,
+    with_transactions: bool,
+) -> anyhow::Result<SyncProgress> {
+    anyhow::ensure!(!interval.is_zero(), "Sampling interval must not be zero");
+
+    let start = Instant::now();
+    let mut progress = SyncProgress::default();
+    let mut transactions = 0;
+
+    for i in 0.. {
+        let next = start + interval * i;
+        if next > start + duration {
+            break;
+        }
+        sleep_until(next).await;
+
+        let block_number = provider.block_number().await?;
+        let highest_block = highest_block(provider, block_number).await?;
+
+        if let Some(previous) = progress.samples.last() {
+            if with_transactions && block_number > previous.block_number {
+                transactions +=
+                    count_transactions(provider, previous.block_number + 1..=block_number).await?;
+            }
+        }
+
+        progress.samples.push(SyncSample {
+            elapsed: start.elapsed(),
+            block_number,
+            highest_block,
+            transactions,
+        });
+    }
+
+    Ok(progress)
+}
+
+///
+/// Samples the sync progress of every node at the same time.
+///
+pub async fn run_sync<P: Provider>(
+    nodes: &[(&str, &P)],
+    interval: Duration,
+    duration: Duration,
+    with_transactions: bool,
+) -> anyhow::Result<BTreeMap<String, SyncProgress>> {
+    let progress = join_all(
+        nodes
+            .iter()
+            .map(|(_, provider)| sample_sync(*provider, interval, duration, with_transactions)),
+    )
+    .await;
+
+    nodes
+        .iter()
+        .zip(progress)
+        .map(|((node, _), progress)| Ok((node.to_string(), progress?)))
+        .collect()
+}
@@ -0,0 +1,629 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use macro_utils::TestConfig;
+use starknet_providers::{JsonRpcClient, Provider};
+use unit_tests::{
+    batch::BatchClient,
+    bench::{
+        baseline::{compare_baselines, Baseline, RegressionOptions},
+        batch::{run_batch, BatchWorkload},
+        latency::{run_latency, LatencyWorkload},
+        load::{run_load, LoadOptions, Mix},
+        pagination::{run_pagination, PaginationWorkload},
+        state_age::{run_state_age, AgeWorkload},
+        sync::run_sync,
+        Method, Targets,
+    },
+    bisect::bisect,
+    constants::{DEOXYS, PATHFINDER},
+    coverage::{load_hits, recorded_versions, CoverageMatrix},
+    drilldown::drilldown,
+    eras::{check_boundaries, find_eras},
+    monitor::{Monitor, MonitorOptions},
+    reconstruct::{reconstruct_and_check, ReconstructedState},
+    replay::{load_capture, replay, Compare, ReplayOptions},
+    sampling::Sampler,
+    spec::OpenRpcSpec,
+    sweep::{sweep, Checkpoint, Component, SweepOptions},
+    transport::DittoTransport,
+};
+use url::Url;
+
+///
+/// Tools to test and benchmark Starknet full nodes, on top of the unit test suite.
+///
+#[derive(Parser)]
+#[command(name = "ditto")]
+struct Cli {
+    /// Node urls, in the same format as used by the unit tests
+    #[arg(long, default_value = "../secret.json")]
+    config: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the spec coverage matrix of calls recorded with `DITTO_COVERAGE=<dir> cargo test`
+    Coverage {
+        /// Directory where calls were recorded
+        dir: PathBuf,
+    },
+    /// Compares a range of blocks between Deoxys and Pathfinder and reports every divergence
+    Sweep {
+        #[arg(long, default_value_t = 0)]
+        from: u64,
+        /// Defaults to the lowest tip of both nodes, or to the end of the checkpoint's range
+        /// when resuming
+        #[arg(long)]
+        to: Option<u64>,
+        /// Components to compare, ex: `block,receipts,state_update,traces`
+        #[arg(long, value_delimiter = ',')]
+        components: Option<Vec<Component>>,
+        /// Number of blocks compared concurrently
+        #[arg(long, default_value_t = 10)]
+        concurrency: usize,
+        /// File where progress is saved, the sweep resumes from it if it exists
+        #[arg(long)]
+        checkpoint: Option<PathBuf>,
+    },
+    /// Finds the first block whose block hash or state root differs between Deoxys and Pathfinder
+    Bisect {
+        #[arg(long, default_value_t = 0)]
+        from: u64,
+        /// Defaults to the lowest tip of both nodes
+        #[arg(long)]
+        to: Option<u64>,
+    },
+    /// Lists the state entries of a block which differ between Deoxys and Pathfinder, as
+    /// (contract, key, expected, actual) with Pathfinder as the reference
+    Drilldown { block: u64 },
+    /// Rebuilds the state of a node from its state updates and checks its point queries
+    /// (getStorageAt, getNonce, getClassHashAt) against it at the given blocks
+    Reconstruct {
+        /// Node to check, `deoxys` or `pathfinder`
+        #[arg(long, default_value = DEOXYS)]
+        node: String,
+        /// Blocks at which point queries are checked, ex: `1000,50000`
+        #[arg(long, value_delimiter = ',', required = true)]
+        blocks: Vec<u64>,
+        /// Number of random entries checked at each block, on top of the entries it wrote
+        #[arg(long, default_value_t = 100)]
+        sample: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// File where the reconstructed state is saved, replay resumes from it if it exists
+        #[arg(long)]
+        state: Option<PathBuf>,
+    },
+    /// Locates the first block of each Starknet version and runs every comparison on the
+    /// blocks around it
+    Eras {
+        /// Number of blocks checked on each side of a boundary
+        #[arg(long, default_value_t = 2)]
+        margin: u64,
+    },
+    /// Follows the tip of Pathfinder and compares each new block with Deoxys, printing
+    /// divergences and sync lag as json alerts, one per line
+    Monitor {
+        /// First block to compare, defaults to the current lowest tip of both nodes
+        #[arg(long)]
+        from: Option<u64>,
+        /// Components to compare, ex: `block,receipts,state_update,traces`
+        #[arg(long, value_delimiter = ',')]
+        components: Option<Vec<Component>>,
+        /// Seconds between two polls of the nodes
+        #[arg(long, default_value_t = 5)]
+        poll_interval: u64,
+        /// Number of blocks Deoxys may be behind Pathfinder before raising an alert
+        #[arg(long, default_value_t = 5)]
+        lag_threshold: u64,
+        /// Number of blocks below the tip of each node checked for reorgs, `0` to disable
+        #[arg(long, default_value_t = 16)]
+        reorg_window: u64,
+    },
+    /// Replays a capture of JSON-RPC calls, one json object per line, against a node at the
+    /// original rate or a scaled one
+    Replay {
+        capture: PathBuf,
+        /// Node to replay against, `deoxys` or `pathfinder`
+        #[arg(long, default_value = DEOXYS)]
+        node: String,
+        /// Factor by which the original rate is multiplied, ex: `2` replays twice as fast
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+        /// Compare responses with those saved in the capture
+        #[arg(long, conflicts_with = "reference")]
+        compare_recorded: bool,
+        /// Compare responses with those of another node, `deoxys` or `pathfinder`
+        #[arg(long)]
+        reference: Option<String>,
+    },
+    /// Benchmarks Deoxys and Pathfinder with the same workload
+    Bench {
+        #[command(subcommand)]
+        bench: Bench,
+    },
+}
+
+#[derive(Subcommand)]
+enum Bench {
+    /// Latency percentiles and error rate of each RPC method, side by side for each node
+    Latency {
+        /// Methods to benchmark, ex: `get_storage_at,call`, defaults to all
+        #[arg(long, value_delimiter = ',')]
+        methods: Option<Vec<Method>>,
+        /// Requests per method and node
+        #[arg(long, default_value_t = 100)]
+        requests: usize,
+        /// Requests in flight at the same time
+        #[arg(long, default_value_t = 1)]
+        concurrency: usize,
+        /// Requests per method and node issued before measuring
+        #[arg(long, default_value_t = 10)]
+        warmup: usize,
+        /// Number of blocks the requests are spread over, sampled up to the lowest tip
+        #[arg(long, default_value_t = 20)]
+        blocks: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// File where the results are saved as a baseline
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Baseline to compare the results with, whose workload and blocks are reused
+        #[arg(
+            long,
+            conflicts_with_all = ["methods", "requests", "concurrency", "warmup", "blocks", "seed"]
+        )]
+        compare: Option<PathBuf>,
+    },
+    /// Compares two saved benchmark runs and reports significant regressions
+    Compare { baseline: PathBuf, current: PathBuf },
+    /// Issues requests at a fixed or ramping rate, regardless of responses, and reports the
+    /// rate at which each node saturates
+    Load {
+        /// Requests per second, or first rate of the ramp
+        #[arg(long)]
+        rate: f64,
+        /// Last rate of the ramp, no ramp if unset
+        #[arg(long)]
+        ramp_to: Option<f64>,
+        /// Rate increase between two steps of the ramp
+        #[arg(long, default_value_t = 10.0)]
+        ramp_step: f64,
+        /// Seconds spent at each rate
+        #[arg(long, default_value_t = 30)]
+        step_duration: u64,
+        /// Share of each method, ex: `get_storage_at=60,call=20,get_events=20`
+        #[arg(long, default_value_t = Mix::default())]
+        mix: Mix,
+        /// Number of blocks the requests are spread over, sampled up to the lowest tip
+        #[arg(long, default_value_t = 20)]
+        blocks: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Samples the sync progress of each node over time and estimates their time to tip
+    Sync {
+        /// Seconds between two samples
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+        /// Seconds during which nodes are sampled
+        #[arg(long, default_value_t = 300)]
+        duration: u64,
+        /// Count the transactions of synced blocks, with one request per block
+        #[arg(long)]
+        transactions: bool,
+    },
+    /// Latency of getStorageAt, getNonce and call on the same contract at block ages spread
+    /// from genesis to the tip
+    StateAge {
+        /// Number of block ages
+        #[arg(long, default_value_t = 10)]
+        buckets: usize,
+        /// Requests per method, node and age
+        #[arg(long, default_value_t = 20)]
+        requests: usize,
+    },
+    /// Throughput of the same calls sent one per HTTP request and in JSON-RPC batches of
+    /// several sizes
+    Batch {
+        #[arg(long, default_value_t = Method::GetStorageAt)]
+        method: Method,
+        /// Calls per node and batch size
+        #[arg(long, default_value_t = 1000)]
+        requests: usize,
+        /// Calls per batch
+        #[arg(long, value_delimiter = ',', default_value = "10,50,100")]
+        batch_sizes: Vec<usize>,
+        /// HTTP requests in flight at the same time
+        #[arg(long, default_value_t = 1)]
+        concurrency: usize,
+        /// Number of blocks the requests are spread over, sampled up to the lowest tip
+        #[arg(long, default_value_t = 20)]
+        blocks: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Drains getEvents over a block range by following continuation tokens, for several
+    /// chunk sizes and filters, and reports events per second and time to first page
+    Events {
+        #[arg(long)]
+        from: u64,
+        #[arg(long)]
+        to: u64,
+        /// Events per page
+        #[arg(long, value_delimiter = ',', default_value = "10,100,1000")]
+        chunk_sizes: Vec<u64>,
+        /// Pages after which a drain stops, unlimited if unset
+        #[arg(long)]
+        max_pages: Option<usize>,
+    },
+}
+
+type Client = JsonRpcClient<DittoTransport>;
+
+/// Lowest tip of both nodes, as the highest block which can be compared
+async fn common_tip(deoxys: &Client, pathfinder: &Client) -> anyhow::Result<u64> {
+    Ok(deoxys
+        .block_number()
+        .await?
+        .min(pathfinder.block_number().await?))
+}
+
+///
+/// Deoxys and Pathfinder clients. Responses are not validated against the spec, unlike
+/// in the unit tests, so that tools see whatever the nodes return.
+///
+fn clients(config: &str) -> anyhow::Result<(Client, Client)> {
+    let config =
+        TestConfig::new(config).with_context(|| format!("'{config}' must contain node urls"))?;
+    let client = |url: &str| -> anyhow::Result<Client> {
+        Ok(JsonRpcClient::new(DittoTransport::new(Url::parse(url)?)))
+    };
+
+    Ok((client(&config.deoxys)?, client(&config.pathfinder)?))
+}
+
+/// Deoxys and Pathfinder clients sending raw JSON-RPC batches
+fn batch_clients(config: &str) -> anyhow::Result<(BatchClient, BatchClient)> {
+    let config =
+        TestConfig::new(config).with_context(|| format!("'{config}' must contain node urls"))?;
+
+    Ok((
+        BatchClient::new(Url::parse(&config.deoxys)?),
+        BatchClient::new(Url::parse(&config.pathfinder)?),
+    ))
+}
+
+/// Fails if `current` regressed since `baseline`
+fn report_regressions(baseline: &Baseline, current: &Baseline) -> anyhow::Result<()> {
+    let (warnings, regressions) =
+        compare_baselines(baseline, current, &RegressionOptions::default());
+
+    for warning in warnings.iter() {
+        log::warn!("{warning}");
+    }
+    for regression in regressions.iter() {
+        println!("{regression}");
+    }
+    anyhow::ensure!(regressions.is_empty(), "{} regressions", regressions.len());
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Coverage { dir } => {
+            for version in recorded_versions(&dir)? {
+                let spec = OpenRpcSpec::load(&version)?;
+                let hits = load_hits(&dir, &version)?;
+
+                println!("{}", CoverageMatrix::new(&spec, &hits));
+            }
+        }
+        Command::Sweep {
+            from,
+            to,
+            components,
+            concurrency,
+            checkpoint,
+        } => {
+            let (deoxys, pathfinder) = clients(&cli.config)?;
+            // an open-ended sweep is resumed up to the tip it was started with
+            let saved = match &checkpoint {
+                Some(path) => Checkpoint::saved(path)?,
+                None => None,
+            };
+            let to = match (to, saved) {
+                (Some(to), _) => to,
+                (None, Some(saved)) => saved.to,
+                (None, None) => common_tip(&deoxys, &pathfinder).await?,
+            };
+            let options = SweepOptions {
+                components: components.unwrap_or_else(|| Component::ALL.to_vec()),
+                concurrency,
+                checkpoint,
+            };
+
+            let report = sweep(&deoxys, &pathfinder, from..=to, &options).await?;
+            println!("{report}");
+
+            anyhow::ensure!(
+                report.divergences.is_empty(),
+                "{} divergent blocks",
+                report.divergent_blocks().len()
+            );
+        }
+        Command::Bisect { from, to } => {
+            let (deoxys, pathfinder) = clients(&cli.config)?;
+            let to = match to {
+                Some(to) => to,
+                None => common_tip(&deoxys, &pathfinder).await?,
+            };
+
+            match bisect(&deoxys, &pathfinder, from..=to).await? {
+                Some(divergence) => println!("{divergence}"),
+                None => println!("Nodes agree on block {to}"),
+            }
+        }
+        Command::Drilldown { block } => {
+            let (deoxys, pathfinder) = clients(&cli.config)?;
+
+            for divergence in drilldown(&deoxys, &pathfinder, block).await? {
+                println!("{divergence}");
+            }
+        }
+        Command::Reconstruct {
+            node,
+            blocks,
+            sample,
+            seed,
+            state: path,
+        } => {
+            let (deoxys, pathfinder) = clients(&cli.config)?;
+            let client = match node.as_str() {
+                DEOXYS => deoxys,
+                PATHFINDER => pathfinder,
+                _ => anyhow::bail!("Unknown node '{node}'"),
+            };
+
+            let mut state = match &path {
+                Some(path) => ReconstructedState::load(path)?,
+                None => ReconstructedState::new(),
+            };
+            let result = reconstruct_and_check(&client, &mut state, &blocks, sample, seed).await;
+            if let Some(path) = &path {
+                state.save(path)?;
+            }
+
+            let mismatches = result?;
+            for mismatch in mismatches.iter() {
+                println!("{mismatch}");
+            }
+            anyhow::ensure!(mismatches.is_empty(), "{} mismatches", mismatches.len());
+        }
+        Command::Eras { margin } => {
+            let (deoxys, pathfinder) = clients(&cli.config)?;
+            let tip = common_tip(&deoxys, &pathfinder).await?;
+
+            let eras = find_eras(&pathfinder, 0..=tip).await?;
+            for era in eras.iter() {
+                println!("{era}");
+            }
+
+            let findings = check_boundaries(&deoxys, &pathfinder, &eras, margin).await?;
+            for finding in findings.iter() {
+                println!("{finding}");
+            }
+            anyhow::ensure!(findings.is_empty(), "{} findings", findings.len());
+        }
+        Command::Monitor {
+            from,
+            components,
+            poll_interval,
+            lag_threshold,
+            reorg_window,
+        } => {
+            let (deoxys, pathfinder) = clients(&cli.config)?;
+            let from = match from {
+                Some(from) => from,
+                None => common_tip(&deoxys, &pathfinder).await?,
+            };
+            let options = MonitorOptions {
+                components: components.unwrap_or_else(|| Component::ALL.to_vec()),
+                poll_interval: Duration::from_secs(poll_interval),
+                lag_threshold,
+                reorg_window,
+            };
+
+            Monitor::new(options, from)
+                .run(&deoxys, &pathfinder, |alert| {
+                    match serde_json::to_string(alert) {
+                        Ok(alert) => println!("{alert}"),
+                        Err(e) => log::error!("Failed to serialize alert {alert}: {e}"),
+                    }
+                })
+                .await;
+        }
+        Command::Replay {
+            capture,
+            node,
+            speed,
+            compare_recorded,
+            reference,
+        } => {
+            let (deoxys, pathfinder) = batch_clients(&cli.config)?;
+            let select = |node: &str| -> anyhow::Result<BatchClient> {
+                match node {
+                    DEOXYS => Ok(deoxys.clone()),
+                    PATHFINDER => Ok(pathfinder.clone()),
+                    _ => anyhow::bail!("Unknown node '{node}'"),
+                }
+            };
+
+            let compare = match reference {
+                Some(reference) => Compare::Reference(select(&reference)?),
+                None if compare_recorded => Compare::Recorded,
+                None => Compare::None,
+            };
+            let options = ReplayOptions {
+                speed,
+                compare,
+                ..Default::default()
+            };
+
+            let report = replay(&select(&node)?, &load_capture(capture)?, &options).await?;
+            print!("{report}");
+            anyhow::ensure!(
+                report.mismatches.is_empty(),
+                "{} mismatches",
+                report.mismatches.len()
+            );
+        }
+        Command::Bench {
+            bench: Bench::Compare { baseline, current },
+        } => {
+            report_regressions(&Baseline::load(baseline)?, &Baseline::load(current)?)?;
+        }
+        Command::Bench { bench } => {
+            let (deoxys, pathfinder) = clients(&cli.config)?;
+            let nodes = [(DEOXYS, &deoxys), (PATHFINDER, &pathfinder)];
+
+            match bench {
+                Bench::Latency {
+                    methods,
+                    requests,
+                    concurrency,
+                    warmup,
+                    blocks,
+                    seed,
+                    save,
+                    compare,
+                } => {
+                    let reference = compare.map(Baseline::load).transpose()?;
+                    let (workload, blocks) = match &reference {
+                        Some(reference) => (reference.workload.clone(), reference.blocks.clone()),
+                        None => {
+                            let tip = common_tip(&deoxys, &pathfinder).await?;
+                            let workload = LatencyWorkload {
+                                methods: methods.unwrap_or_else(|| Method::ALL.to_vec()),
+                                requests,
+                                concurrency,
+                                warmup,
+                            };
+                            (workload, Sampler::with_seed(seed).uniform(0..=tip, blocks))
+                        }
+                    };
+                    let targets = Targets::fetch(&pathfinder, blocks.clone()).await?;
+
+                    let report = run_latency(&nodes, &targets, &workload).await;
+                    print!("{report}");
+
+                    let current = Baseline::new(&nodes, workload, blocks, report).await?;
+                    if let Some(path) = save {
+                        current.save(path)?;
+                    }
+                    if let Some(reference) = reference {
+                        report_regressions(&reference, &current)?;
+                    }
+                }
+                Bench::Compare { .. } => unreachable!("Comparing runs does not need nodes"),
+                Bench::Load {
+                    rate,
+                    ramp_to,
+                    ramp_step,
+                    step_duration,
+                    mix,
+                    blocks,
+                    seed,
+                } => {
+                    let tip = common_tip(&deoxys, &pathfinder).await?;
+                    let blocks = Sampler::with_seed(seed).uniform(0..=tip, blocks);
+                    let targets = Targets::fetch(&pathfinder, blocks).await?;
+                    let step_duration = Duration::from_secs(step_duration);
+                    let options = LoadOptions {
+                        mix,
+                        seed,
+                        ..match ramp_to {
+                            Some(to) => LoadOptions::ramp(rate, to, ramp_step, step_duration),
+                            None => LoadOptions::fixed(rate, step_duration),
+                        }
+                    };
+
+                    print!("{}", run_load(&nodes, &targets, &options).await?);
+                }
+                Bench::Sync {
+                    interval,
+                    duration,
+                    transactions,
+                } => {
+                    let progress = run_sync(
+                        &nodes,
+                        Duration::from_secs(interval),
+                        Duration::from_secs(duration),
+                        transactions,
+                    )
+                    .await?;
+
+                    for (node, progress) in progress.iter() {
+                        println!("{node}:\n{progress}");
+                    }
+                }
+                Bench::StateAge { buckets, requests } => {
+                    let workload = AgeWorkload {
+                        buckets,
+                        requests,
+                        ..Default::default()
+                    };
+
+                    print!("{}", run_state_age(&nodes, &workload).await?);
+                }
+                Bench::Batch {
+                    method,
+                    requests,
+                    batch_sizes,
+                    concurrency,
+                    blocks,
+                    seed,
+                } => {
+                    let tip = common_tip(&deoxys, &pathfinder).await?;
+                    let blocks = Sampler::with_seed(seed).uniform(0..=tip, blocks);
+                    let targets = Targets::fetch(&pathfinder, blocks).await?;
+                    let workload = BatchWorkload {
+                        method,
+                        requests,
+                        batch_sizes,
+                        concurrency,
+                    };
+                    let (batch_deoxys, batch_pathfinder) = batch_clients(&cli.config)?;
+                    let nodes = [(DEOXYS, &batch_deoxys), (PATHFINDER, &batch_pathfinder)];
+
+                    print!("{}", run_batch(&nodes, &targets, &workload).await);
+                }
+                Bench::Events {
+                    from,
+                    to,
+                    chunk_sizes,
+                    max_pages,
+                } => {
+                    let workload = PaginationWorkload {
+                        chunk_sizes,
+                        max_pages,
+                        ..PaginationWorkload::new(from, to)
+                    };
+
+                    print!("{}", run_pagination(&nodes, &workload).await?);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use std::{fmt, ops::RangeInclusive};
+
+use anyhow::bail;
+use serde_json::Value;
+use starknet_core::types::{
+    BlockId, BlockWithTxHashes, MaybePendingBlockWithTxHashes, MaybePendingStateUpdate,
+};
+use starknet_providers::Provider;
+
+use crate::{
+    constants::{DEOXYS, PATHFINDER},
+    diff::{first_difference, sort_arrays},
+};
+
+///
+/// Part of a block compared by [bisect].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BisectComponent {
+    BlockHash,
+    StateRoot,
+    StateDiff,
+}
+
+impl fmt::Display for BisectComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlockHash => write!(f, "block hash"),
+            Self::StateRoot => write!(f, "state root"),
+            Self::StateDiff => write!(f, "state diff"),
+        }
+    }
+}
+
+///
+/// First block on which Deoxys and Pathfinder disagree.
+///
+#[derive(Debug, Clone)]
+pub struct FirstDivergence {
+    pub block_number: u64,
+    pub components: Vec<(BisectComponent, String)>,
+    /// Number of blocks compared to find the divergence
+    pub steps: u32,
+}
+
+impl fmt::Display for FirstDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "First divergent block is {} (found in {} steps)",
+            self.block_number, self.steps
+        )?;
+        for (component, message) in self.components.iter() {
+            writeln!(f, "  {component}: {message}")?;
+        }
+        Ok(())
+    }
+}
+
+async fn header<P: Provider>(
+    provider: &P,
+    name: &str,
+    block_number: u64,
+) -> anyhow::Result<BlockWithTxHashes> {
+    match provider
+        .get_block_with_tx_hashes(BlockId::Number(block_number))
+        .await?
+    {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok(block),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+            bail!("{name}: expected block {block_number}, got pending block")
+        }
+    }
+}
+
+async fn state_diff<P: Provider>(
+    provider: &P,
+    name: &str,
+    block_number: u64,
+) -> anyhow::Result<Value> {
+    match provider
+        .get_state_update(BlockId::Number(block_number))
+        .await?
+    {
+        MaybePendingStateUpdate::Update(update) => {
+            let mut state_diff = serde_json::to_value(update.state_diff)?;
+            sort_arrays(&mut state_diff);
+            Ok(state_diff)
+        }
+        MaybePendingStateUpdate::PendingUpdate(_) => {
+            bail!("{name}: expected state update {block_number}, got pending state update")
+        }
+    }
+}
+
+/// Block hashes and state roots both commit to the whole chain up to the block
+async fn diverges<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    block_number: u64,
+) -> anyhow::Result<bool> {
+    let a = header(deoxys, DEOXYS, block_number).await?;
+    let b = header(pathfinder, PATHFINDER, block_number).await?;
+
+    Ok(a.block_hash != b.block_hash || a.new_root != b.new_root)
+}
+
+///
+/// Compares block hash, state root and state diff of a block between Deoxys and Pathfinder,
+/// and returns the components which differ.
+///
+pub async fn compare_components<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    block_number: u64,
+) -> anyhow::Result<Vec<(BisectComponent, String)>> {
+    let a = header(deoxys, DEOXYS, block_number).await?;
+    let b = header(pathfinder, PATHFINDER, block_number).await?;
+    let mut components = vec![];
+
+    if a.block_hash != b.block_hash {
+        let parent = if a.parent_hash == b.parent_hash {
+            "same parent"
+        } else {
+            "different parents"
+        };
+        components.push((
+            BisectComponent::BlockHash,
+            format!(
+                "{DEOXYS} has 0x{:064x}, {PATHFINDER} has 0x{:064x} ({parent})",
+                a.block_hash, b.block_hash
+            ),
+        ));
+    }
+    if a.new_root != b.new_root {
+        components.push((
+            BisectComponent::StateRoot,
+            format!(
+                "{DEOXYS} has 0x{:064x}, {PATHFINDER} has 0x{:064x}",
+                a.new_root, b.new_root
+            ),
+        ));
+    }
+
+    let diff_a = state_diff(deoxys, DEOXYS, block_number).await?;
+    let diff_b = state_diff(pathfinder, PATHFINDER, block_number).await?;
+    if let Some(difference) = first_difference(&diff_a, &diff_b) {
+        components.push((
+            BisectComponent::StateDiff,
+            format!(
+                "{}: {DEOXYS} has {}, {PATHFINDER} has {}",
+                difference.pointer, difference.left, difference.right
+            ),
+        ));
+    }
+
+    Ok(components)
+}
+
+///
+/// Binary-searches `range` for the first block whose block hash or state root differs
+/// between Deoxys and Pathfinder, and reports every component which differs on that block.
+/// Returns `None` if the nodes agree on the last block of the range.
+///
+/// This relies on divergences persisting once they appear, which holds for state roots and
+/// block hashes as they commit to all previous blocks. A divergence which is later fixed,
+/// ex: a block hash computed differently for a range of old blocks, can be missed.
+///
+pub async fn bisect<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    range: RangeInclusive<u64>,
+) -> anyhow::Result<Option<FirstDivergence>> {
+    anyhow::ensure!(!range.is_empty(), "Empty block range {range:?}");
+    let (mut good, mut bad) = (*range.start(), *range.end());
+    let mut steps = 2;
+
+    if !diverges(deoxys, pathfinder, bad).await? {
+        return Ok(None);
+    }
+
+    let block_number = if diverges(deoxys, pathfinder, good).await? {
+        good
+    } else {
+        // invariant: the nodes agree on `good` and disagree on `bad`
+        while bad - good > 1 {
+            let middle = good + (bad - good) / 2;
+            steps += 1;
+
+            if diverges(deoxys, pathfinder, middle).await? {
+                bad = middle;
+            } else {
+                good = middle;
+            }
+            log::info!("Divergence is in {good}..={bad}");
+        }
+        bad
+    };
+
+    Ok(Some(FirstDivergence {
+        block_number,
+        components: compare_components(deoxys, pathfinder, block_number).await?,
+        steps,
+    }))
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::Context;
+use serde_json::{json, Value};
+
+pub use macro_utils::CASSETTE_ENV;
+
+use crate::{
+    batch::Request,
+    replay::{load_capture, CaptureRecorder, CapturedCall},
+    transport::{RawResponse, RpcObserver},
+};
+
+/// Directory of the cassettes, relative to the `unit_tests` crate where tests run
+pub const CASSETTE_DIR: &str = "cassettes";
+
+const SPEC_VERSION: &str = "starknet_specVersion";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Calls are sent to the nodes and saved to the test's cassettes
+    Record,
+    /// Calls are answered from the test's cassettes, without network access
+    Replay,
+}
+
+impl CassetteMode {
+    /// Mode set by `DITTO_CASSETTE`, `None` if tests run against live nodes
+    pub fn from_env() -> Option<Self> {
+        match std::env::var(CASSETTE_ENV).as_deref() {
+            Ok("record") => Some(Self::Record),
+            Ok("replay") => Some(Self::Replay),
+            Ok(mode) => panic!("Unknown {CASSETTE_ENV} '{mode}', expected 'record' or 'replay'"),
+            Err(_) => None,
+        }
+    }
+}
+
+///
+/// Cassette of the running test for `node`, at
+/// `cassettes/<test binary>/<test name>/<node>.jsonl`. The test name is that of the thread
+/// the test harness runs the test on.
+///
+pub fn cassette_path(node: &str) -> anyhow::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let binary = exe
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .context("Invalid test binary name")?;
+    // cargo appends a hash to test binaries, ex: `test_get_events-0123456789abcdef`
+    let binary = binary.rsplit_once('-').map_or(binary, |(name, _)| name);
+
+    let thread = std::thread::current();
+    let test = match thread.name() {
+        Some("main") | None => {
+            anyhow::bail!("Cassettes need the test name, tests must run on their own thread")
+        }
+        Some(name) => name.replace("::", "/"),
+    };
+
+    Ok(Path::new(CASSETTE_DIR)
+        .join(binary)
+        .join(test)
+        .join(format!("{node}.jsonl")))
+}
+
+///
+/// Starts recording a cassette at `path`, replacing any previous recording. The node's
+/// spec version is saved first so that fixtures can be built from the cassette alone.
+///
+pub fn record(path: &Path, spec_version: &str) -> anyhow::Result<CaptureRecorder> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+
+    let recorder = CaptureRecorder::new(path, true)?;
+    let response = RawResponse::Success {
+        result: json!(spec_version),
+    };
+    recorder.observe(SPEC_VERSION, &json!([]), &response)?;
+
+    Ok(recorder)
+}
+
+///
+/// Recorded calls of a test against a node. A call which was made several times is
+/// answered with its recorded responses in order, then with the last one.
+///
+pub struct Cassette {
+    calls: Vec<CapturedCall>,
+    /// Responses already served for each request
+    served: Mutex<HashMap<String, usize>>,
+}
+
+impl Cassette {
+    pub fn new(calls: Vec<CapturedCall>) -> Self {
+        Self {
+            calls,
+            served: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let calls = load_capture(path).with_context(|| {
+            format!(
+                "No cassette at {}, record it with {CASSETTE_ENV}=record",
+                path.display()
+            )
+        })?;
+
+        Ok(Self::new(calls))
+    }
+
+    /// Spec version of the node when the cassette was recorded
+    pub fn spec_version(&self) -> Option<String> {
+        self.calls
+            .iter()
+            .find(|call| call.request.method == SPEC_VERSION)
+            .and_then(|call| match &call.response {
+                Some(RawResponse::Success { result }) => result.as_str().map(str::to_string),
+                _ => None,
+            })
+    }
+
+    /// Next recorded response to `method` called with `params`
+    pub fn response(&self, method: &str, params: &Value) -> Option<RawResponse> {
+        let request = Request::new(method, params.clone());
+        let responses = self
+            .calls
+            .iter()
+            .filter(|call| call.request == request)
+            .filter_map(|call| call.response.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut served = self.served.lock().unwrap();
+        let served = served
+            .entry(serde_json::to_string(&request).ok()?)
+            .or_default();
+        let response = (*responses.get(*served).or(responses.last())?).clone();
+        *served += 1;
+
+        Some(response)
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::Read;
+
+use anyhow::{anyhow, bail, Context};
+use flate2::read::GzDecoder;
+use starknet_core::types::{
+    contract::legacy::{LegacyContractClass, LegacyProgram},
+    BlockId, CompressedLegacyContractClass, ContractClass, FieldElement,
+};
+use starknet_providers::Provider;
+
+///
+/// Computes the class hash of a contract class as returned by `starknet_getClass` or
+/// `starknet_getClassAt`.
+///
+/// Sierra classes are hashed directly. Legacy (Cairo 0) classes are served with a
+/// gzipped program, so the program is decompressed and the class is rebuilt before
+/// hashing. The hinted class hash quirks (pythonic json serialization, missing
+/// `debug_info`, legacy `accessible_scopes` attributes) are handled by starknet-core.
+///
+pub fn compute_class_hash(class: &ContractClass) -> anyhow::Result<FieldElement> {
+    match class {
+        ContractClass::Sierra(class) => Ok(class.class_hash()),
+        ContractClass::Legacy(class) => legacy_class_hash(class),
+    }
+}
+
+fn legacy_class_hash(class: &CompressedLegacyContractClass) -> anyhow::Result<FieldElement> {
+    let mut program = String::new();
+    GzDecoder::new(&class.program[..])
+        .read_to_string(&mut program)
+        .context("Failed to decompress legacy program")?;
+
+    let program = serde_json::from_str::<LegacyProgram>(&program)
+        .context("Decompressed legacy program is not valid json")?;
+
+    // RPC and compiler artifacts share the same json representation for the abi and
+    // entry points, so we go through serde rather than converting field by field
+    let abi = match &class.abi {
+        Some(abi) => serde_json::from_value(serde_json::to_value(abi)?)?,
+        None => vec![],
+    };
+    let entry_points_by_type =
+        serde_json::from_value(serde_json::to_value(&class.entry_points_by_type)?)?;
+
+    let class = LegacyContractClass {
+        abi,
+        entry_points_by_type,
+        program,
+    };
+
+    class
+        .class_hash()
+        .map_err(|e| anyhow!("Failed to compute legacy class hash: {e}"))
+}
+
+///
+/// Retrieves a class with `starknet_getClass` and checks that it hashes to the requested
+/// class hash.
+///
+pub async fn verify_class<P: Provider>(
+    provider: &P,
+    block_id: BlockId,
+    class_hash: FieldElement,
+) -> anyhow::Result<()> {
+    let class = provider.get_class(block_id, class_hash).await?;
+    let computed = compute_class_hash(&class)?;
+
+    if computed != class_hash {
+        bail!(
+            "getClass: requested class hash 0x{class_hash:064x}, class hashes to 0x{computed:064x}"
+        );
+    }
+
+    Ok(())
+}
+
+///
+/// Retrieves the class of a contract with `starknet_getClassAt` and checks that it hashes
+/// to the class hash returned by `starknet_getClassHashAt` for the same contract and block.
+///
+/// Returns the verified class hash.
+///
+pub async fn verify_class_at<P: Provider>(
+    provider: &P,
+    block_id: BlockId,
+    contract_address: FieldElement,
+) -> anyhow::Result<FieldElement> {
+    let class = provider.get_class_at(block_id, contract_address).await?;
+    let class_hash = provider
+        .get_class_hash_at(block_id, contract_address)
+        .await?;
+    let computed = compute_class_hash(&class)?;
+
+    if computed != class_hash {
+        bail!(
+            "getClassAt: contract 0x{contract_address:064x} has class hash 0x{class_hash:064x}, class hashes to 0x{computed:064x}"
+        );
+    }
+
+    Ok(class_hash)
+}
@@ -0,0 +1,241 @@
+use std::{fmt, ops::RangeInclusive};
+
+use anyhow::bail;
+use futures::{stream, StreamExt};
+use serde_json::Value;
+use starknet_core::types::{
+    BlockId, BlockWithTxHashes, BlockWithTxs, FieldElement, MaybePendingBlockWithTxHashes,
+    MaybePendingBlockWithTxs, MaybePendingTransactionReceipt, Transaction,
+};
+use starknet_providers::Provider;
+
+/// Number of transactions checked concurrently inside a block
+const TRANSACTION_CONCURRENCY: usize = 16;
+
+///
+/// Disagreement between two RPC methods of the same node about a block.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inconsistency {
+    pub block_number: u64,
+    pub method: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "block {}: {}: {}",
+            self.block_number, self.method, self.message
+        )
+    }
+}
+
+///
+/// Checks that `getBlockTransactionCount`, `getBlockWithTxHashes`, `getBlockWithTxs`,
+/// `getTransactionByBlockIdAndIndex`, `getTransactionByHash` and `getTransactionReceipt`
+/// agree on the transactions of a block: count, hashes, indices and block membership.
+///
+/// RPC errors are returned as an error, disagreements as [Inconsistency]s.
+///
+pub async fn check_block<P: Provider>(
+    provider: &P,
+    block_number: u64,
+) -> anyhow::Result<Vec<Inconsistency>> {
+    let block_id = BlockId::Number(block_number);
+    let mut inconsistencies = vec![];
+    let mut report = |method: &'static str, message: String| {
+        inconsistencies.push(Inconsistency {
+            block_number,
+            method,
+            message,
+        })
+    };
+
+    let count = provider.get_block_transaction_count(block_id).await?;
+    let block_with_tx_hashes = match provider.get_block_with_tx_hashes(block_id).await? {
+        MaybePendingBlockWithTxHashes::Block(block) => block,
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+            bail!("getBlockWithTxHashes: expected block {block_number}, got pending block")
+        }
+    };
+    let block_with_txs = match provider.get_block_with_txs(block_id).await? {
+        MaybePendingBlockWithTxs::Block(block) => block,
+        MaybePendingBlockWithTxs::PendingBlock(_) => {
+            bail!("getBlockWithTxs: expected block {block_number}, got pending block")
+        }
+    };
+
+    if block_with_tx_hashes.block_number != block_number {
+        report(
+            "getBlockWithTxHashes",
+            format!("returned block {}", block_with_tx_hashes.block_number),
+        );
+    }
+    if let Some(message) = compare_headers(&block_with_tx_hashes, &block_with_txs)? {
+        report("getBlockWithTxs", message);
+    }
+
+    let hashes = &block_with_tx_hashes.transactions;
+    let transactions = &block_with_txs.transactions;
+    if hashes.len() as u64 != count {
+        report(
+            "getBlockWithTxHashes",
+            format!(
+                "{} transactions, getBlockTransactionCount is {count}",
+                hashes.len()
+            ),
+        );
+    }
+    if transactions.len() as u64 != count {
+        report(
+            "getBlockWithTxs",
+            format!(
+                "{} transactions, getBlockTransactionCount is {count}",
+                transactions.len()
+            ),
+        );
+    }
+
+    for (index, (hash, transaction)) in hashes.iter().zip(transactions.iter()).enumerate() {
+        if transaction.transaction_hash() != hash {
+            report(
+                "getBlockWithTxs",
+                format!(
+                    "transaction {index} is 0x{:064x}, getBlockWithTxHashes has 0x{hash:064x}",
+                    transaction.transaction_hash()
+                ),
+            );
+        }
+    }
+
+    let block_hash = block_with_txs.block_hash;
+    let results = stream::iter(transactions.iter().enumerate())
+        .map(|(index, transaction)| {
+            check_transaction(
+                provider,
+                block_number,
+                block_hash,
+                index as u64,
+                transaction,
+            )
+        })
+        .buffered(TRANSACTION_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    for result in results {
+        for (method, message) in result? {
+            report(method, message);
+        }
+    }
+
+    Ok(inconsistencies)
+}
+
+///
+/// Checks every block in `range`, stopping at the first RPC error.
+///
+pub async fn check_range<P: Provider>(
+    provider: &P,
+    range: RangeInclusive<u64>,
+) -> anyhow::Result<Vec<Inconsistency>> {
+    let mut inconsistencies = vec![];
+    for block_number in range {
+        inconsistencies.extend(check_block(provider, block_number).await?);
+    }
+
+    Ok(inconsistencies)
+}
+
+/// Block headers, without their transactions
+fn compare_headers(
+    block_with_tx_hashes: &BlockWithTxHashes,
+    block_with_txs: &BlockWithTxs,
+) -> anyhow::Result<Option<String>> {
+    let header = |block: Value| match block {
+        Value::Object(mut object) => {
+            object.remove("transactions");
+            Value::Object(object)
+        }
+        block => block,
+    };
+
+    let a = header(serde_json::to_value(block_with_tx_hashes)?);
+    let b = header(serde_json::to_value(block_with_txs)?);
+
+    Ok((a != b).then(|| format!("header {b} differs from getBlockWithTxHashes header {a}")))
+}
+
+async fn check_transaction<P: Provider>(
+    provider: &P,
+    block_number: u64,
+    block_hash: FieldElement,
+    index: u64,
+    transaction: &Transaction,
+) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let block_id = BlockId::Number(block_number);
+    let hash = *transaction.transaction_hash();
+    let mut inconsistencies = vec![];
+
+    let by_index = provider
+        .get_transaction_by_block_id_and_index(block_id, index)
+        .await?;
+    if &by_index != transaction {
+        inconsistencies.push((
+            "getTransactionByBlockIdAndIndex",
+            format!(
+                "transaction {index} is 0x{:064x}, expected 0x{hash:064x}",
+                by_index.transaction_hash()
+            ),
+        ));
+    }
+
+    let by_hash = provider.get_transaction_by_hash(hash).await?;
+    if &by_hash != transaction {
+        inconsistencies.push((
+            "getTransactionByHash",
+            format!("transaction 0x{hash:064x} differs from getBlockWithTxs"),
+        ));
+    }
+
+    let receipt = match provider.get_transaction_receipt(hash).await? {
+        MaybePendingTransactionReceipt::Receipt(receipt) => serde_json::to_value(receipt)?,
+        MaybePendingTransactionReceipt::PendingReceipt(_) => {
+            inconsistencies.push((
+                "getTransactionReceipt",
+                format!("transaction 0x{hash:064x} has a pending receipt"),
+            ));
+            return Ok(inconsistencies);
+        }
+    };
+
+    let felt = |field: &str| {
+        receipt[field]
+            .as_str()
+            .and_then(|value| FieldElement::from_hex_be(value).ok())
+    };
+    if felt("transaction_hash") != Some(hash) {
+        inconsistencies.push((
+            "getTransactionReceipt",
+            format!(
+                "receipt of 0x{hash:064x} has transaction_hash {}",
+                receipt["transaction_hash"]
+            ),
+        ));
+    }
+    if felt("block_hash") != Some(block_hash)
+        || receipt["block_number"].as_u64() != Some(block_number)
+    {
+        inconsistencies.push((
+            "getTransactionReceipt",
+            format!(
+                "receipt of 0x{hash:064x} is in block {} ({}), expected {block_number} (0x{block_hash:064x})",
+                receipt["block_number"], receipt["block_hash"]
+            ),
+        ));
+    }
+
+    Ok(inconsistencies)
+}
@@ -0,0 +1,350 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    spec::{OpenRpcSpec, SpecError},
+    transport::{RawResponse, RpcObserver},
+};
+
+///
+/// Environment variable enabling coverage recording in the test fixtures. Must be set to
+/// the directory where hits are recorded, one `<spec version>.jsonl` file per spec version.
+///
+pub const COVERAGE_ENV: &str = "DITTO_COVERAGE";
+
+/// Json-RPC keys holding a block id, ex: `block_id` or the `from_block` of an event filter
+const BLOCK_ID_KEYS: [&str; 3] = ["block_id", "from_block", "to_block"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockIdForm {
+    Hash,
+    Number,
+    Latest,
+    Pending,
+}
+
+impl BlockIdForm {
+    pub const ALL: [Self; 4] = [Self::Hash, Self::Number, Self::Latest, Self::Pending];
+
+    fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(tag) if tag == "latest" => Some(Self::Latest),
+            Value::String(tag) if tag == "pending" => Some(Self::Pending),
+            Value::Object(object) if object.contains_key("block_hash") => Some(Self::Hash),
+            Value::Object(object) if object.contains_key("block_number") => Some(Self::Number),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BlockIdForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hash => write!(f, "hash"),
+            Self::Number => write!(f, "number"),
+            Self::Latest => write!(f, "latest"),
+            Self::Pending => write!(f, "pending"),
+        }
+    }
+}
+
+///
+/// A single RPC call as seen by the [CoverageRecorder].
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hit {
+    pub method: String,
+    /// Names of the params which were sent, empty for params passed by position
+    pub params: Vec<String>,
+    pub block_ids: Vec<BlockIdForm>,
+    pub error: Option<i64>,
+}
+
+impl Hit {
+    pub fn new(method: &str, params: &Value, response: &RawResponse) -> Self {
+        let params_names = match params {
+            Value::Object(object) => object
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(name, _)| name.clone())
+                .collect(),
+            _ => vec![],
+        };
+
+        let mut block_ids = vec![];
+        find_block_ids(params, &mut block_ids);
+
+        let error = match response {
+            RawResponse::Success { .. } => None,
+            RawResponse::Error { error } => Some(error.code),
+        };
+
+        Self {
+            method: method.to_string(),
+            params: params_names,
+            block_ids,
+            error,
+        }
+    }
+}
+
+fn find_block_ids(value: &Value, block_ids: &mut Vec<BlockIdForm>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter() {
+                match BlockIdForm::from_json(value) {
+                    Some(form) if BLOCK_ID_KEYS.contains(&key.as_str()) => block_ids.push(form),
+                    _ => find_block_ids(value, block_ids),
+                }
+            }
+        }
+        Value::Array(array) => {
+            for value in array.iter() {
+                match BlockIdForm::from_json(value) {
+                    // positional params, block ids are never nested in another array
+                    Some(form) => block_ids.push(form),
+                    None => find_block_ids(value, block_ids),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+///
+/// Records every RPC call made by the test suite as json lines, so that coverage can be
+/// aggregated across test binaries.
+///
+pub struct CoverageRecorder {
+    file: Mutex<File>,
+}
+
+impl CoverageRecorder {
+    pub fn new(dir: impl AsRef<Path>, version: &str) -> anyhow::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        let path = hits_path(dir, version);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Could not open {}", path.display()))?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl RpcObserver for CoverageRecorder {
+    fn observe(&self, method: &str, params: &Value, response: &RawResponse) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(&Hit::new(method, params, response))?;
+        line.push('\n');
+
+        self.file.lock().unwrap().write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn hits_path(dir: impl AsRef<Path>, version: &str) -> PathBuf {
+    dir.as_ref().join(format!("{version}.jsonl"))
+}
+
+pub fn load_hits(dir: impl AsRef<Path>, version: &str) -> anyhow::Result<Vec<Hit>> {
+    let path = hits_path(dir, version);
+    let file = File::open(&path).with_context(|| format!("Could not open {}", path.display()))?;
+
+    BufReader::new(file)
+        .lines()
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+///
+/// Spec versions for which hits were recorded in `dir`.
+///
+pub fn recorded_versions(dir: impl AsRef<Path>) -> anyhow::Result<Vec<String>> {
+    let mut versions = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "jsonl")
+        {
+            if let Some(version) = path.file_stem().and_then(|stem| stem.to_str()) {
+                versions.push(version.to_string());
+            }
+        }
+    }
+
+    versions.sort();
+    Ok(versions)
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodCoverage {
+    pub method: String,
+    pub calls: usize,
+    pub successes: usize,
+    /// `None` if the method does not take a block id
+    pub block_ids: Option<BTreeMap<BlockIdForm, usize>>,
+    pub params: BTreeMap<String, usize>,
+    pub errors: Vec<(SpecError, usize)>,
+    /// Errors returned by the node which the spec does not list for this method
+    pub unlisted_errors: BTreeMap<i64, usize>,
+}
+
+///
+/// Coverage of a spec version by the test suite: method × block id form × error code.
+///
+#[derive(Debug, Clone)]
+pub struct CoverageMatrix {
+    pub version: String,
+    pub methods: Vec<MethodCoverage>,
+}
+
+impl CoverageMatrix {
+    pub fn new(spec: &OpenRpcSpec, hits: &[Hit]) -> Self {
+        let methods = spec
+            .methods
+            .iter()
+            .map(|method| {
+                let hits = hits.iter().filter(|hit| hit.method == method.name);
+
+                let takes_block_id = method
+                    .params
+                    .iter()
+                    .any(|param| spec.references(&param.schema, "BLOCK_ID"));
+
+                let mut coverage = MethodCoverage {
+                    method: method.name.clone(),
+                    calls: 0,
+                    successes: 0,
+                    block_ids: takes_block_id
+                        .then(|| BlockIdForm::ALL.iter().map(|form| (*form, 0)).collect()),
+                    params: method
+                        .params
+                        .iter()
+                        .map(|param| (param.name.clone(), 0))
+                        .collect(),
+                    errors: method.errors.iter().map(|e| (e.clone(), 0)).collect(),
+                    unlisted_errors: BTreeMap::new(),
+                };
+
+                for hit in hits {
+                    coverage.calls += 1;
+
+                    for param in hit.params.iter() {
+                        if let Some(count) = coverage.params.get_mut(param) {
+                            *count += 1;
+                        }
+                    }
+
+                    if let Some(block_ids) = coverage.block_ids.as_mut() {
+                        for form in hit.block_ids.iter().copied().collect::<HashSet<_>>() {
+                            *block_ids.entry(form).or_default() += 1;
+                        }
+                    }
+
+                    match hit.error {
+                        None => coverage.successes += 1,
+                        Some(code) => {
+                            match coverage.errors.iter_mut().find(|(e, _)| e.code == code) {
+                                Some((_, count)) => *count += 1,
+                                None => *coverage.unlisted_errors.entry(code).or_default() += 1,
+                            }
+                        }
+                    }
+                }
+
+                coverage
+            })
+            .collect();
+
+        Self {
+            version: spec.version.clone(),
+            methods,
+        }
+    }
+}
+
+fn cell(count: usize) -> String {
+    if count == 0 {
+        String::from("✗")
+    } else {
+        count.to_string()
+    }
+}
+
+impl fmt::Display for CoverageMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "## Spec {}\n", self.version)?;
+
+        write!(f, "| method | calls | success |")?;
+        for form in BlockIdForm::ALL {
+            write!(f, " {form} |")?;
+        }
+        writeln!(f, " params | errors |")?;
+        writeln!(f, "|---|---|---|---|---|---|---|---|---|")?;
+
+        for method in self.methods.iter() {
+            write!(
+                f,
+                "| {} | {} | {} |",
+                method.method,
+                method.calls,
+                cell(method.successes)
+            )?;
+
+            for form in BlockIdForm::ALL {
+                match &method.block_ids {
+                    Some(block_ids) => write!(f, " {} |", cell(block_ids[&form]))?,
+                    None => write!(f, " - |")?,
+                }
+            }
+
+            let params = method
+                .params
+                .iter()
+                .map(|(name, n)| format!("{name}: {}", cell(*n)))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let errors = method
+                .errors
+                .iter()
+                .map(|(e, n)| format!("{} ({}): {}", e.name, e.code, cell(*n)))
+                .chain(
+                    method
+                        .unlisted_errors
+                        .iter()
+                        .map(|(code, n)| format!("UNLISTED ({code}): {n}")),
+                )
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(f, " {params} | {errors} |")?;
+        }
+
+        let total = self.methods.len();
+        let called = self.methods.iter().filter(|m| m.calls > 0).count();
+        let errors = self.methods.iter().flat_map(|m| m.errors.iter());
+        let errors_hit = errors.clone().filter(|(_, n)| *n > 0).count();
+
+        writeln!(
+            f,
+            "\n{called}/{total} methods called, {errors_hit}/{} error cases covered",
+            errors.count()
+        )
+    }
+}
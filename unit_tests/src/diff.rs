@@ -0,0 +1,84 @@
+use std::fmt;
+
+use serde_json::Value;
+
+///
+/// A leaf which differs between two json values. Missing object keys and array items
+/// are represented as `null`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Json pointer to the leaf, ex: `/transactions/3/max_fee`
+    pub pointer: String,
+    pub left: Value,
+    pub right: Value,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {} != {}", self.left, self.right)
+    }
+}
+
+///
+/// First difference between `left` and `right`, in document order.
+///
+pub fn first_difference(left: &Value, right: &Value) -> Option<Difference> {
+    walk(String::new(), left, right)
+}
+
+fn walk(pointer: String, left: &Value, right: &Value) -> Option<Difference> {
+    if left == right {
+        return None;
+    }
+
+    match (left, right) {
+        (Value::Object(l), Value::Object(r)) => {
+            let mut keys = l.keys().chain(r.keys().filter(|key| !l.contains_key(*key)));
+            keys.find_map(|key| {
+                walk(
+                    format!("{pointer}/{}", escape(key)),
+                    l.get(key).unwrap_or(&Value::Null),
+                    r.get(key).unwrap_or(&Value::Null),
+                )
+            })
+        }
+        (Value::Array(l), Value::Array(r)) => (0..l.len().max(r.len())).find_map(|i| {
+            walk(
+                format!("{pointer}/{i}"),
+                l.get(i).unwrap_or(&Value::Null),
+                r.get(i).unwrap_or(&Value::Null),
+            )
+        }),
+        _ => Some(Difference {
+            pointer,
+            left: left.clone(),
+            right: right.clone(),
+        }),
+    }
+}
+
+/// Json pointer escaping, see RFC 6901
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+///
+/// Recursively sorts every array of `value`, for documents whose arrays are sets rather
+/// than lists, ex: state diffs, which nodes are free to return in any order.
+///
+pub fn sort_arrays(value: &mut Value) {
+    match value {
+        Value::Object(object) => object.values_mut().for_each(sort_arrays),
+        Value::Array(array) => {
+            array.iter_mut().for_each(sort_arrays);
+            array.sort_by_cached_key(|item| item.to_string());
+        }
+        _ => {}
+    }
+}
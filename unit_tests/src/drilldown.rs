@@ -0,0 +1,276 @@
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use starknet_core::types::{
+    BlockId, FieldElement, MaybePendingStateUpdate, StarknetError, StateDiff,
+};
+use starknet_providers::{Provider, ProviderError};
+
+use crate::constants::{DEOXYS, PATHFINDER};
+
+///
+/// A single entry of the state, as written by a state diff.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StateEntry {
+    Storage {
+        contract: FieldElement,
+        key: FieldElement,
+    },
+    Nonce {
+        contract: FieldElement,
+    },
+    /// Written by deployed and replaced classes
+    ClassHash {
+        contract: FieldElement,
+    },
+    /// Written by declared classes, `0x0` for Cairo 0 classes
+    CompiledClassHash {
+        class_hash: FieldElement,
+    },
+}
+
+impl StateEntry {
+    /// Contract, or class for declared classes
+    pub fn contract(&self) -> FieldElement {
+        match self {
+            Self::Storage { contract, .. }
+            | Self::Nonce { contract }
+            | Self::ClassHash { contract } => *contract,
+            Self::CompiledClassHash { class_hash } => *class_hash,
+        }
+    }
+}
+
+impl fmt::Display for StateEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Storage { key, .. } => write!(f, "0x{key:064x}"),
+            Self::Nonce { .. } => write!(f, "nonce"),
+            Self::ClassHash { .. } => write!(f, "class_hash"),
+            Self::CompiledClassHash { .. } => write!(f, "compiled_class_hash"),
+        }
+    }
+}
+
+///
+/// Flattens a state diff into the value written to each [StateEntry].
+///
+pub fn state_entries(state_diff: &StateDiff) -> BTreeMap<StateEntry, FieldElement> {
+    let mut entries = BTreeMap::new();
+
+    for diff in state_diff.storage_diffs.iter() {
+        for entry in diff.storage_entries.iter() {
+            entries.insert(
+                StateEntry::Storage {
+                    contract: diff.address,
+                    key: entry.key,
+                },
+                entry.value,
+            );
+        }
+    }
+    for nonce in state_diff.nonces.iter() {
+        entries.insert(
+            StateEntry::Nonce {
+                contract: nonce.contract_address,
+            },
+            nonce.nonce,
+        );
+    }
+    for deployed in state_diff.deployed_contracts.iter() {
+        entries.insert(
+            StateEntry::ClassHash {
+                contract: deployed.address,
+            },
+            deployed.class_hash,
+        );
+    }
+    for replaced in state_diff.replaced_classes.iter() {
+        entries.insert(
+            StateEntry::ClassHash {
+                contract: replaced.contract_address,
+            },
+            replaced.class_hash,
+        );
+    }
+    for declared in state_diff.declared_classes.iter() {
+        entries.insert(
+            StateEntry::CompiledClassHash {
+                class_hash: declared.class_hash,
+            },
+            declared.compiled_class_hash,
+        );
+    }
+    for class_hash in state_diff.deprecated_declared_classes.iter() {
+        entries.insert(
+            StateEntry::CompiledClassHash {
+                class_hash: *class_hash,
+            },
+            FieldElement::ZERO,
+        );
+    }
+
+    entries
+}
+
+///
+/// A state entry on which Deoxys and Pathfinder disagree after a block. Pathfinder is used
+/// as the reference, `None` means the entry does not exist.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDivergence {
+    pub entry: StateEntry,
+    /// Pathfinder's value
+    pub expected: Option<FieldElement>,
+    /// Deoxys's value
+    pub actual: Option<FieldElement>,
+    /// The nodes already disagreed on this entry at the parent block
+    pub inherited: bool,
+    /// The nodes agree on the entry at the block, which only differs in their state diffs.
+    /// `expected` and `actual` are then the values written by the state diffs.
+    pub unresolved: bool,
+}
+
+impl fmt::Display for StateDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: Option<FieldElement>| match value {
+            Some(value) => format!("0x{value:064x}"),
+            None => String::from("none"),
+        };
+
+        write!(
+            f,
+            "(0x{:064x}, {}, {}, {})",
+            self.entry.contract(),
+            self.entry,
+            value(self.expected),
+            value(self.actual)
+        )?;
+        if self.inherited {
+            write!(f, " already at parent block")?;
+        }
+        if self.unresolved {
+            write!(f, " in state diffs only")?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) async fn state_diff<P: Provider>(
+    provider: &P,
+    name: &str,
+    block_number: u64,
+) -> anyhow::Result<StateDiff> {
+    match provider
+        .get_state_update(BlockId::Number(block_number))
+        .await?
+    {
+        MaybePendingStateUpdate::Update(update) => Ok(update.state_diff),
+        MaybePendingStateUpdate::PendingUpdate(_) => {
+            bail!("{name}: expected state update {block_number}, got pending state update")
+        }
+    }
+}
+
+///
+/// Current value of a state entry at `block_id`. Compiled class hashes can not be queried
+/// and are always `None`.
+///
+pub async fn query_entry<P: Provider>(
+    provider: &P,
+    entry: StateEntry,
+    block_id: BlockId,
+) -> anyhow::Result<Option<FieldElement>> {
+    let result = match entry {
+        StateEntry::Storage { contract, key } => {
+            provider.get_storage_at(contract, key, block_id).await
+        }
+        StateEntry::Nonce { contract } => provider.get_nonce(block_id, contract).await,
+        StateEntry::ClassHash { contract } => provider.get_class_hash_at(block_id, contract).await,
+        StateEntry::CompiledClassHash { .. } => return Ok(None),
+    };
+
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+///
+/// Compares the state update of a block between Deoxys and Pathfinder, then confirms each
+/// differing entry with `getStorageAt`, `getNonce` and `getClassHashAt` at the block and its
+/// parent.
+///
+/// Entries which differ in the state diffs but have the same value on both nodes at the
+/// block, ex: a write of an unchanged value, can not be confirmed and are reported as
+/// unresolved.
+///
+pub async fn drilldown<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    block_number: u64,
+) -> anyhow::Result<Vec<StateDivergence>> {
+    let block_id = BlockId::Number(block_number);
+    let actual = state_entries(&state_diff(deoxys, DEOXYS, block_number).await?);
+    let expected = state_entries(&state_diff(pathfinder, PATHFINDER, block_number).await?);
+
+    let mut candidates = actual
+        .keys()
+        .chain(expected.keys())
+        .filter(|entry| actual.get(entry) != expected.get(entry))
+        .copied()
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates.dedup();
+
+    let mut divergences = vec![];
+    for entry in candidates {
+        if let StateEntry::CompiledClassHash { .. } = entry {
+            divergences.push(StateDivergence {
+                entry,
+                expected: expected.get(&entry).copied(),
+                actual: actual.get(&entry).copied(),
+                inherited: false,
+                unresolved: false,
+            });
+            continue;
+        }
+
+        let value_deoxys = query_entry(deoxys, entry, block_id).await?;
+        let value_pathfinder = query_entry(pathfinder, entry, block_id).await?;
+        if value_deoxys == value_pathfinder {
+            log::debug!("{entry:?} differs in state diffs but not in state");
+            divergences.push(StateDivergence {
+                entry,
+                expected: expected.get(&entry).copied(),
+                actual: actual.get(&entry).copied(),
+                inherited: false,
+                unresolved: true,
+            });
+            continue;
+        }
+
+        let inherited = match block_number.checked_sub(1) {
+            Some(parent) => {
+                let parent = BlockId::Number(parent);
+                query_entry(deoxys, entry, parent).await?
+                    != query_entry(pathfinder, entry, parent).await?
+            }
+            None => false,
+        };
+
+        divergences.push(StateDivergence {
+            entry,
+            expected: value_pathfinder,
+            actual: value_deoxys,
+            inherited,
+            unresolved: false,
+        });
+    }
+
+    Ok(divergences)
+}
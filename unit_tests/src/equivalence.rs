@@ -0,0 +1,238 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+use starknet_core::{
+    types::{
+        BlockId, BlockTag, EventFilter, FieldElement, FunctionCall, MaybePendingBlockWithTxHashes,
+    },
+    utils::get_selector_from_name,
+};
+use starknet_providers::{Provider, ProviderError};
+
+use crate::constants::{CONTRACT_KEY, STARKGATE_ETH_CONTRACT_ADDR};
+
+///
+/// RPC methods which take a block id and must answer the same for every form of that
+/// block id: number, hash and, at the tip, the `latest` tag.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockScopedMethod {
+    GetBlockWithTxHashes,
+    GetBlockWithTxs,
+    GetBlockTransactionCount,
+    GetTransactionByBlockIdAndIndex,
+    GetStateUpdate,
+    GetStorageAt,
+    GetNonce,
+    GetClassHashAt,
+    Call,
+    GetEvents,
+    TraceBlockTransactions,
+}
+
+impl BlockScopedMethod {
+    pub const ALL: [Self; 11] = [
+        Self::GetBlockWithTxHashes,
+        Self::GetBlockWithTxs,
+        Self::GetBlockTransactionCount,
+        Self::GetTransactionByBlockIdAndIndex,
+        Self::GetStateUpdate,
+        Self::GetStorageAt,
+        Self::GetNonce,
+        Self::GetClassHashAt,
+        Self::Call,
+        Self::GetEvents,
+        Self::TraceBlockTransactions,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::GetBlockWithTxHashes => "starknet_getBlockWithTxHashes",
+            Self::GetBlockWithTxs => "starknet_getBlockWithTxs",
+            Self::GetBlockTransactionCount => "starknet_getBlockTransactionCount",
+            Self::GetTransactionByBlockIdAndIndex => "starknet_getTransactionByBlockIdAndIndex",
+            Self::GetStateUpdate => "starknet_getStateUpdate",
+            Self::GetStorageAt => "starknet_getStorageAt",
+            Self::GetNonce => "starknet_getNonce",
+            Self::GetClassHashAt => "starknet_getClassHashAt",
+            Self::Call => "starknet_call",
+            Self::GetEvents => "starknet_getEvents",
+            Self::TraceBlockTransactions => "starknet_traceBlockTransactions",
+        }
+    }
+}
+
+///
+/// Contract state queried by the state-scoped methods (`getStorageAt`, `getNonce`,
+/// `getClassHashAt`, `call`).
+///
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pub contract_address: FieldElement,
+    pub storage_key: FieldElement,
+    pub call: FunctionCall,
+}
+
+impl Default for Probe {
+    /// StarkGate ETH, which exists for most of the chain's history
+    fn default() -> Self {
+        let contract_address = FieldElement::from_hex_be(STARKGATE_ETH_CONTRACT_ADDR).unwrap();
+
+        Self {
+            contract_address,
+            storage_key: FieldElement::from_hex_be(CONTRACT_KEY).unwrap(),
+            call: FunctionCall {
+                contract_address,
+                entry_point_selector: get_selector_from_name("name").unwrap(),
+                calldata: vec![],
+            },
+        }
+    }
+}
+
+/// Result of a call, serialized so that results of all methods can be compared alike
+pub type CallResult = Result<Value, String>;
+
+///
+/// A method which did not answer the same for every form of a block id.
+///
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub method: BlockScopedMethod,
+    pub block_number: u64,
+    pub results: Vec<(BlockId, CallResult)>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} at block {}:", self.method.name(), self.block_number)?;
+        for (block_id, result) in self.results.iter() {
+            match result {
+                Ok(value) => writeln!(f, "  {block_id:?} => {value}")?,
+                Err(e) => writeln!(f, "  {block_id:?} => error: {e}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn serialize<T: Serialize>(result: Result<T, ProviderError>) -> CallResult {
+    match result {
+        Ok(value) => serde_json::to_value(value).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub async fn call_method<P: Provider>(
+    provider: &P,
+    method: BlockScopedMethod,
+    block_id: BlockId,
+    probe: &Probe,
+) -> CallResult {
+    match method {
+        BlockScopedMethod::GetBlockWithTxHashes => {
+            serialize(provider.get_block_with_tx_hashes(block_id).await)
+        }
+        BlockScopedMethod::GetBlockWithTxs => {
+            serialize(provider.get_block_with_txs(block_id).await)
+        }
+        BlockScopedMethod::GetBlockTransactionCount => {
+            serialize(provider.get_block_transaction_count(block_id).await)
+        }
+        BlockScopedMethod::GetTransactionByBlockIdAndIndex => serialize(
+            provider
+                .get_transaction_by_block_id_and_index(block_id, 0)
+                .await,
+        ),
+        BlockScopedMethod::GetStateUpdate => serialize(provider.get_state_update(block_id).await),
+        BlockScopedMethod::GetStorageAt => serialize(
+            provider
+                .get_storage_at(probe.contract_address, probe.storage_key, block_id)
+                .await,
+        ),
+        BlockScopedMethod::GetNonce => {
+            serialize(provider.get_nonce(block_id, probe.contract_address).await)
+        }
+        BlockScopedMethod::GetClassHashAt => serialize(
+            provider
+                .get_class_hash_at(block_id, probe.contract_address)
+                .await,
+        ),
+        BlockScopedMethod::Call => serialize(provider.call(probe.call.clone(), block_id).await),
+        BlockScopedMethod::GetEvents => serialize(
+            provider
+                .get_events(
+                    EventFilter {
+                        from_block: Some(block_id),
+                        to_block: Some(block_id),
+                        address: None,
+                        keys: None,
+                    },
+                    None,
+                    100,
+                )
+                .await,
+        ),
+        BlockScopedMethod::TraceBlockTransactions => {
+            serialize(provider.trace_block_transactions(block_id).await)
+        }
+    }
+}
+
+///
+/// Calls every [BlockScopedMethod] on `block_number` with the block number, the block hash
+/// and, if the block is the tip, the `latest` tag, and returns the methods whose results
+/// differ between forms.
+///
+/// If the tip moves while the methods are called, `latest` results are discarded as they
+/// may refer to different blocks.
+///
+pub async fn check_block_id_equivalence<P: Provider>(
+    provider: &P,
+    block_number: u64,
+    probe: &Probe,
+) -> anyhow::Result<Vec<Mismatch>> {
+    let block_hash = match provider
+        .get_block_with_tx_hashes(BlockId::Number(block_number))
+        .await?
+    {
+        MaybePendingBlockWithTxHashes::Block(block) => block.block_hash,
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+            anyhow::bail!("Expected block {block_number}, got pending block")
+        }
+    };
+
+    let tip = provider.block_hash_and_number().await?;
+    let mut block_ids = vec![BlockId::Number(block_number), BlockId::Hash(block_hash)];
+    if tip.block_hash == block_hash {
+        block_ids.push(BlockId::Tag(BlockTag::Latest));
+    }
+
+    let mut results = vec![];
+    for method in BlockScopedMethod::ALL {
+        let mut method_results = vec![];
+        for block_id in block_ids.iter() {
+            let result = call_method(provider, method, *block_id, probe).await;
+            method_results.push((*block_id, result));
+        }
+        results.push((method, method_results));
+    }
+
+    if block_ids.len() > 2 && provider.block_hash_and_number().await?.block_hash != block_hash {
+        log::warn!("Tip moved past block {block_number}, ignoring latest results");
+        for (_, method_results) in results.iter_mut() {
+            method_results.retain(|(block_id, _)| *block_id != BlockId::Tag(BlockTag::Latest));
+        }
+    }
+
+    Ok(results
+        .into_iter()
+        .filter(|(_, method_results)| method_results.windows(2).any(|pair| pair[0].1 != pair[1].1))
+        .map(|(method, method_results)| Mismatch {
+            method,
+            block_number,
+            results: method_results,
+        })
+        .collect())
+}
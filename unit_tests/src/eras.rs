@@ -0,0 +1,201 @@
+use std::{collections::BTreeMap, fmt, ops::RangeInclusive};
+
+use anyhow::bail;
+use starknet_core::types::{BlockId, MaybePendingBlockWithTxHashes};
+use starknet_providers::Provider;
+
+use crate::{
+    consistency::check_block,
+    constants::{DEOXYS, PATHFINDER},
+    equivalence::{check_block_id_equivalence, Probe},
+    sweep::{compare_block, Component},
+};
+
+///
+/// Range of blocks produced by a single Starknet version.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era {
+    /// Empty for blocks older than 0.9.1 (block 3800), whose headers have no version
+    pub version: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Era {
+    pub fn blocks(&self) -> RangeInclusive<u64> {
+        self.start..=self.end
+    }
+}
+
+impl fmt::Display for Era {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = if self.version.is_empty() {
+            "unversioned"
+        } else {
+            &self.version
+        };
+        write!(f, "{version} ({}..={})", self.start, self.end)
+    }
+}
+
+///
+/// `starknet_version` of a block header.
+///
+pub async fn block_version<P: Provider>(provider: &P, block_number: u64) -> anyhow::Result<String> {
+    match provider
+        .get_block_with_tx_hashes(BlockId::Number(block_number))
+        .await?
+    {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok(block.starknet_version),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+            bail!("Expected block {block_number}, got pending block")
+        }
+    }
+}
+
+///
+/// Splits `range` into [Era]s by binary-searching the blocks where `starknet_version`
+/// changes. Versions only ever increase along the chain, so two blocks with the same
+/// version are assumed to be in the same era.
+///
+pub async fn find_eras<P: Provider>(
+    provider: &P,
+    range: RangeInclusive<u64>,
+) -> anyhow::Result<Vec<Era>> {
+    anyhow::ensure!(!range.is_empty(), "Empty block range {range:?}");
+    let (start, end) = (*range.start(), *range.end());
+
+    let mut versions = BTreeMap::new();
+    versions.insert(start, block_version(provider, start).await?);
+    versions.insert(end, block_version(provider, end).await?);
+
+    // first block of every era but the first
+    let mut boundaries = vec![];
+    let mut segments = vec![(start, end)];
+
+    while let Some((low, high)) = segments.pop() {
+        if versions[&low] == versions[&high] {
+            continue;
+        }
+        if high - low == 1 {
+            boundaries.push(high);
+            continue;
+        }
+
+        let middle = low + (high - low) / 2;
+        let version = block_version(provider, middle).await?;
+        log::debug!("Block {middle} is version '{version}'");
+        versions.insert(middle, version);
+
+        segments.push((low, middle));
+        segments.push((middle, high));
+    }
+
+    boundaries.sort();
+
+    let mut eras = vec![];
+    let mut era_start = start;
+    for boundary in boundaries.into_iter().chain([end + 1]) {
+        eras.push(Era {
+            version: versions[&era_start].clone(),
+            start: era_start,
+            end: boundary - 1,
+        });
+        era_start = boundary;
+    }
+
+    Ok(eras)
+}
+
+///
+/// The `margin` last blocks of each era and the `margin` first blocks of the next one,
+/// sorted and without duplicates.
+///
+pub fn boundary_blocks(eras: &[Era], margin: u64) -> Vec<u64> {
+    if margin == 0 {
+        return vec![];
+    }
+
+    let mut blocks = eras
+        .windows(2)
+        .flat_map(|pair| {
+            let before = pair[0].end.saturating_sub(margin - 1).max(pair[0].start)..=pair[0].end;
+            let after = pair[1].start..=pair[1].end.min(pair[1].start + margin - 1);
+            before.chain(after)
+        })
+        .collect::<Vec<_>>();
+    blocks.sort();
+    blocks.dedup();
+    blocks
+}
+
+///
+/// Something wrong found by [check_boundaries] on a block.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundaryFinding {
+    pub block_number: u64,
+    pub check: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for BoundaryFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "block {}: {}: {}",
+            self.block_number, self.check, self.message
+        )
+    }
+}
+
+///
+/// Runs every comparison on the blocks around each era boundary: differential comparison
+/// of blocks, receipts, state updates and traces between Deoxys and Pathfinder, then
+/// cross-method consistency and block id equivalence on each node.
+///
+pub async fn check_boundaries<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    eras: &[Era],
+    margin: u64,
+) -> anyhow::Result<Vec<BoundaryFinding>> {
+    let probe = Probe::default();
+    let mut findings = vec![];
+
+    for block_number in boundary_blocks(eras, margin) {
+        log::info!("Checking boundary block {block_number}");
+
+        let (_, divergences) =
+            compare_block(deoxys, pathfinder, block_number, &Component::ALL).await;
+        findings.extend(divergences.into_iter().map(|divergence| BoundaryFinding {
+            block_number,
+            check: "sweep",
+            message: format!("{}: {}", divergence.component, divergence.message),
+        }));
+
+        for (name, provider) in [(DEOXYS, deoxys), (PATHFINDER, pathfinder)] {
+            for inconsistency in check_block(provider, block_number).await? {
+                findings.push(BoundaryFinding {
+                    block_number,
+                    check: "consistency",
+                    message: format!(
+                        "{name}: {}: {}",
+                        inconsistency.method, inconsistency.message
+                    ),
+                });
+            }
+
+            for mismatch in check_block_id_equivalence(provider, block_number, &probe).await? {
+                findings.push(BoundaryFinding {
+                    block_number,
+                    check: "block id equivalence",
+                    message: format!("{name}: {}", mismatch.method.name()),
+                });
+            }
+        }
+    }
+
+    Ok(findings)
+}
@@ -0,0 +1,58 @@
+use starknet_core::types::{BlockId, EventFilter, EventsPage, FieldElement};
+use starknet_providers::{Provider, ProviderError};
+
+///
+/// Filter and page size of a `starknet_getEvents` query.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventQuery {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub address: Option<FieldElement>,
+    pub keys: Option<Vec<Vec<FieldElement>>>,
+    pub chunk_size: u64,
+}
+
+impl EventQuery {
+    /// Events of a single block matching `keys`
+    pub fn block(block_nu: u64, keys: &[Vec<FieldElement>], chunk_size: u64) -> Self {
+        Self {
+            from_block: block_nu,
+            to_block: block_nu,
+            address: None,
+            keys: Some(keys.to_vec()),
+            chunk_size,
+        }
+    }
+}
+
+///
+/// A single page of the events matching `query`, starting at `continuation_token`.
+///
+pub async fn get_events<P: Provider>(
+    provider: &P,
+    query: &EventQuery,
+    continuation_token: Option<String>,
+) -> Result<EventsPage, ProviderError> {
+    provider
+        .get_events(
+            EventFilter {
+                // getEvents is a applied through a filter
+                // this filter consists of a block range...
+                from_block: Some(BlockId::Number(query.from_block)),
+                to_block: Some(BlockId::Number(query.to_block)),
+                // a beginning contract address...
+                address: query.address,
+                // and keys used to filter out events. Keys can include a hash of the event
+                // and even event return values for further filtering
+                keys: query.keys.clone(),
+            },
+            // in cases were a first search does not yield enough results, a continuation key
+            // can be used to keep searching from the point of the last getEvent search
+            continuation_token,
+            // chunk size marks the number of events to look through and filter
+            // this means that there cannot be more than chunk_size events returned by getEvents
+            query.chunk_size,
+        )
+        .await
+}
@@ -1,39 +1,205 @@
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 
+use anyhow::anyhow;
 use macro_utils::TestConfig;
 use rstest::fixture;
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient};
+use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use tokio::runtime;
 use url::Url;
 
+use crate::batch::BatchClient;
+use crate::cassette::{self, cassette_path, Cassette, CassetteMode};
 use crate::constants::*;
+use crate::coverage::{CoverageRecorder, COVERAGE_ENV};
 use crate::map;
+use crate::replay::{CaptureRecorder, CAPTURE_ENV};
+use crate::sampling::{Sampler, SEED_ENV};
+use crate::spec::{OpenRpcSpec, SpecValidator, SUPPORTED_VERSIONS};
+use crate::transport::DittoTransport;
+
+/// Spec versions by node url, or the error met while querying them
+static SPEC_VERSIONS: OnceLock<Mutex<HashMap<String, Result<String, String>>>> = OnceLock::new();
+/// Spec validators by spec version, `None` if the spec version is not supported
+static VALIDATORS: OnceLock<Mutex<HashMap<String, Option<Arc<SpecValidator>>>>> = OnceLock::new();
+/// Node url used when replaying cassettes without a `secret.json`, it is never called
+const OFFLINE_URL: &str = "http://localhost";
 
 #[fixture]
 pub fn config() -> TestConfig {
-    TestConfig::new("../secret.json").expect("'../secret.json' must contain correct node urls")
+    let config = TestConfig::new("../secret.json");
+
+    match CassetteMode::from_env() {
+        Some(CassetteMode::Replay) => config.unwrap_or(TestConfig {
+            deoxys: String::from(OFFLINE_URL),
+            pathfinder: String::from(OFFLINE_URL),
+        }),
+        _ => config.expect("'../secret.json' must contain correct node urls"),
+    }
 }
 
 #[fixture]
-pub fn deoxys(config: TestConfig) -> JsonRpcClient<HttpTransport> {
-    JsonRpcClient::new(HttpTransport::new(
+pub fn deoxys(config: TestConfig) -> JsonRpcClient<DittoTransport> {
+    JsonRpcClient::new(transport(
+        DEOXYS,
         Url::parse(&config.deoxys).expect("Error parsing Deoxys node url"),
     ))
 }
 
 #[fixture]
-pub fn pathfinder(config: TestConfig) -> JsonRpcClient<HttpTransport> {
-    JsonRpcClient::new(HttpTransport::new(
+pub fn pathfinder(config: TestConfig) -> JsonRpcClient<DittoTransport> {
+    JsonRpcClient::new(transport(
+        PATHFINDER,
         Url::parse(&config.pathfinder).expect("Error parsing Deoxys node url"),
     ))
 }
 
 #[fixture]
 pub fn clients(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
-) -> HashMap<String, JsonRpcClient<HttpTransport>> {
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) -> HashMap<String, JsonRpcClient<DittoTransport>> {
     map! {
         String::from(DEOXYS) => deoxys,
         String::from(PATHFINDER) => pathfinder,
     }
 }
+
+#[fixture]
+pub fn batch_deoxys(config: TestConfig) -> BatchClient {
+    BatchClient::new(Url::parse(&config.deoxys).expect("Error parsing Deoxys node url"))
+}
+
+#[fixture]
+pub fn batch_pathfinder(config: TestConfig) -> BatchClient {
+    BatchClient::new(Url::parse(&config.pathfinder).expect("Error parsing Pathfinder node url"))
+}
+
+/// Sampler with a fixed seed when using cassettes, so that the same blocks are requested
+#[fixture]
+pub fn sampler() -> Sampler {
+    match CassetteMode::from_env() {
+        Some(_) if std::env::var(SEED_ENV).is_err() => Sampler::with_seed(0),
+        _ => Sampler::new(),
+    }
+}
+
+///
+/// Creates a transport which captures calls if `DITTO_CAPTURE` is set, records spec
+/// coverage if `DITTO_COVERAGE` is set and validates every response against the OpenRPC
+/// spec implemented by the node.
+///
+/// If `DITTO_CASSETTE` is set to `record`, the calls of the running test are saved to its
+/// cassette for `node`. If it is set to `replay`, they are answered from that cassette
+/// and `url` is never called.
+///
+pub fn transport(node: &str, url: Url) -> DittoTransport {
+    let mode = CassetteMode::from_env();
+    let (version, mut transport) = match mode {
+        Some(CassetteMode::Replay) => {
+            let cassette = cassette_path(node)
+                .and_then(Cassette::load)
+                .expect("Failed to load cassette");
+            let version = cassette
+                .spec_version()
+                .expect("Cassette does not record the node's spec version");
+            (Ok(version), DittoTransport::replay(cassette))
+        }
+        _ => (node_spec_version(&url), DittoTransport::new(url.clone())),
+    };
+
+    // recorders are attached before the validator, which stops the other observers on
+    // the first invalid response, so that invalid responses are recorded as well
+    if let Ok(path) = std::env::var(CAPTURE_ENV) {
+        let recorder = CaptureRecorder::new(path, true).expect("Failed to create capture file");
+        transport = transport.with_observer(Arc::new(recorder));
+    }
+
+    // a node which cannot be reached fails the test's own requests instead
+    let version = match version {
+        Ok(version) => version,
+        Err(e) => {
+            log::warn!("{e:#}, responses from {url} will not be validated");
+            return transport;
+        }
+    };
+
+    if mode == Some(CassetteMode::Record) {
+        let recorder = cassette_path(node)
+            .and_then(|path| cassette::record(&path, &version))
+            .expect("Failed to record cassette");
+        transport = transport.with_observer(Arc::new(recorder));
+    }
+
+    if let Ok(dir) = std::env::var(COVERAGE_ENV) {
+        let recorder =
+            CoverageRecorder::new(dir, &version).expect("Failed to create coverage recorder");
+        transport = transport.with_observer(Arc::new(recorder));
+    }
+
+    match spec_validator(&version) {
+        Some(validator) => transport = transport.with_observer(validator),
+        None => {
+            log::warn!(
+                "Spec {version} is not supported, responses from {url} will not be validated"
+            )
+        }
+    }
+
+    transport
+}
+
+///
+/// Validator of the spec `version`, `None` if the node implements a version which is not
+/// supported. Panics if a supported version cannot be loaded, as validation would
+/// otherwise be silently turned off.
+///
+fn spec_validator(version: &str) -> Option<Arc<SpecValidator>> {
+    let validators = VALIDATORS.get_or_init(|| Mutex::new(HashMap::new()));
+    // a panic below leaves the cache untouched, later tests fail with the same error
+    let mut validators = validators.lock().unwrap_or_else(PoisonError::into_inner);
+
+    validators
+        .entry(version.to_string())
+        .or_insert_with(|| match OpenRpcSpec::load(version) {
+            Ok(spec) => Some(Arc::new(
+                SpecValidator::new(&spec).expect("Failed to compile spec"),
+            )),
+            Err(e) if SUPPORTED_VERSIONS.contains(&version) => {
+                panic!("Spec {version} is missing, run specs/fetch.sh: {e:#}")
+            }
+            Err(e) => {
+                log::warn!("{e:#}");
+                None
+            }
+        })
+        .clone()
+}
+
+///
+/// Queries the node's spec version, outside of the test's runtime as fixtures are sync.
+/// Versions, or the error met while querying them, are cached by node url so that each
+/// node is only queried once per test binary.
+///
+fn node_spec_version(url: &Url) -> anyhow::Result<String> {
+    let versions = SPEC_VERSIONS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut versions = versions.lock().unwrap();
+
+    versions
+        .entry(url.to_string())
+        .or_insert_with(|| {
+            let client = JsonRpcClient::new(HttpTransport::new(url.clone()));
+
+            std::thread::scope(|s| {
+                s.spawn(|| {
+                    let rt = runtime::Runtime::new().unwrap();
+                    rt.block_on(client.spec_version())
+                        .map_err(|e| format!("Error while getting the spec version of {url}: {e}"))
+                })
+                .join()
+                .unwrap()
+            })
+        })
+        .clone()
+        .map_err(|e| anyhow!(e))
+}
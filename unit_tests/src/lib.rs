@@ -7,12 +7,36 @@ use starknet_core::{
     types::{BroadcastedInvokeTransaction, BroadcastedTransaction, FieldElement},
     utils::get_selector_from_name,
 };
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient};
+use starknet_providers::JsonRpcClient;
 use starknet_signers::{LocalWallet, SigningKey};
+use transport::DittoTransport;
 
+pub mod batch;
+pub mod bench;
+pub mod bisect;
+pub mod cassette;
+pub mod class_hash;
+pub mod consistency;
 pub mod constants;
+pub mod coverage;
+pub mod diff;
+pub mod drilldown;
+pub mod equivalence;
+pub mod eras;
+pub mod events;
 pub mod fixtures;
 pub mod macros;
+pub mod monitor;
+pub mod pending;
+pub mod reconstruct;
+pub mod reorg;
+pub mod replay;
+pub mod sampling;
+pub mod spec;
+pub mod sweep;
+pub mod tip;
+pub mod trace;
+pub mod transport;
 
 pub trait TransactionFactory {
     fn build(nonce: Option<FieldElement>) -> BroadcastedTransaction;
@@ -76,10 +100,10 @@ impl TransactionFactory for MaxFeeTransactionFactory {
     }
 }
 
-type RpcAccount<'a> = SingleOwnerAccount<&'a JsonRpcClient<HttpTransport>, LocalWallet>;
+type RpcAccount<'a> = SingleOwnerAccount<&'a JsonRpcClient<DittoTransport>, LocalWallet>;
 
 pub fn build_single_owner_account<'a>(
-    rpc: &'a JsonRpcClient<HttpTransport>,
+    rpc: &'a JsonRpcClient<DittoTransport>,
     private_key: &str,
     account_address: &str,
     is_legacy: bool,
@@ -114,7 +138,7 @@ pub trait PrepareInvoke {
     ) -> BroadcastedInvokeTransaction;
 }
 
-impl PrepareInvoke for SingleOwnerAccount<&JsonRpcClient<HttpTransport>, LocalWallet> {
+impl PrepareInvoke for SingleOwnerAccount<&JsonRpcClient<DittoTransport>, LocalWallet> {
     async fn prepare_invoke(
         &self,
         calls: Vec<Call>,
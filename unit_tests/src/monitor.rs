@@ -0,0 +1,263 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use starknet_providers::Provider;
+use tokio::time::sleep;
+
+use crate::{
+    constants::{DEOXYS, PATHFINDER},
+    reorg::{block_hashes, ReorgTracker},
+    sweep::{compare_block, Component, Divergence},
+};
+
+#[derive(Debug, Clone)]
+pub struct MonitorOptions {
+    pub components: Vec<Component>,
+    pub poll_interval: Duration,
+    /// Number of blocks Deoxys may be behind Pathfinder before raising an alert
+    pub lag_threshold: u64,
+    /// Number of blocks below the tip of each node checked for reorgs, `0` to disable
+    pub reorg_window: u64,
+}
+
+impl Default for MonitorOptions {
+    fn default() -> Self {
+        Self {
+            components: Component::ALL.to_vec(),
+            poll_interval: Duration::from_secs(5),
+            lag_threshold: 5,
+            reorg_window: 16,
+        }
+    }
+}
+
+///
+/// Something raised by the [Monitor], serialized as a single json object so alerts can be
+/// forwarded as is, ex: `{"alert":"sync_recovered","node":"deoxys","lag":0}`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "alert", rename_all = "snake_case")]
+pub enum Alert {
+    Divergence(Divergence),
+    /// `node` is more than the lag threshold behind the reference node
+    SyncLag {
+        node: String,
+        tip: u64,
+        reference_tip: u64,
+        lag: u64,
+    },
+    /// `node` caught up after a [Alert::SyncLag]
+    SyncRecovered {
+        node: String,
+        lag: u64,
+    },
+    /// `node` replaced `depth` blocks from block `start`
+    Reorg {
+        node: String,
+        start: u64,
+        depth: u64,
+    },
+    /// All nodes agree again on the blocks of a reorg, whose comparisons are re-run
+    ReorgConverged {
+        start: u64,
+        depth: u64,
+        /// Time each node took to settle on the final blocks
+        convergence_ms: BTreeMap<String, u64>,
+    },
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Divergence(divergence) => write!(f, "Divergence: {divergence}"),
+            Self::SyncLag {
+                node,
+                tip,
+                reference_tip,
+                lag,
+            } => write!(
+                f,
+                "Sync lag: {node} is at block {tip}, {lag} blocks behind {PATHFINDER} ({reference_tip})"
+            ),
+            Self::SyncRecovered { node, lag } => {
+                write!(f, "Sync recovered: {node} is {lag} blocks behind {PATHFINDER}")
+            }
+            Self::Reorg { node, start, depth } => {
+                write!(f, "Reorg: {node} replaced {depth} blocks from block {start}")
+            }
+            Self::ReorgConverged {
+                start,
+                depth,
+                convergence_ms,
+            } => {
+                write!(f, "Reorg converged: {depth} blocks from block {start}, after")?;
+                for (node, ms) in convergence_ms.iter() {
+                    write!(f, " {node} {ms}ms")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+///
+/// Follows the tip of Pathfinder, the reference node, and compares each new block with
+/// Deoxys as soon as both nodes have it. Blocks near the tip of each node are watched for
+/// reorgs, and comparisons of reorganized blocks are re-run once both nodes agree on them.
+///
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    options: MonitorOptions,
+    /// Next block to compare
+    next: u64,
+    lagging: bool,
+    reorgs: ReorgTracker,
+}
+
+impl Monitor {
+    /// Monitor comparing blocks from `start` onwards
+    pub fn new(options: MonitorOptions, start: u64) -> Self {
+        Self {
+            options,
+            next: start,
+            lagging: false,
+            reorgs: ReorgTracker::new(),
+        }
+    }
+
+    pub fn next_block(&self) -> u64 {
+        self.next
+    }
+
+    ///
+    /// Checks the sync lag of Deoxys and reorgs on both nodes, then compares every block
+    /// both nodes have which was not compared yet.
+    ///
+    pub async fn poll<P: Provider>(
+        &mut self,
+        deoxys: &P,
+        pathfinder: &P,
+    ) -> anyhow::Result<Vec<Alert>> {
+        let reference_tip = pathfinder.block_number().await?;
+        let tip = deoxys.block_number().await?;
+        let lag = reference_tip.saturating_sub(tip);
+        let mut alerts = vec![];
+
+        if lag > self.options.lag_threshold && !self.lagging {
+            self.lagging = true;
+            alerts.push(Alert::SyncLag {
+                node: DEOXYS.to_string(),
+                tip,
+                reference_tip,
+                lag,
+            });
+        } else if lag <= self.options.lag_threshold && self.lagging {
+            self.lagging = false;
+            alerts.push(Alert::SyncRecovered {
+                node: DEOXYS.to_string(),
+                lag,
+            });
+        }
+
+        if self.options.reorg_window > 0 {
+            alerts.extend(
+                self.check_reorgs(deoxys, pathfinder, tip, reference_tip)
+                    .await?,
+            );
+        }
+
+        for block_number in self.next..=tip.min(reference_tip) {
+            log::info!("Comparing block {block_number}");
+            alerts.extend(self.compare(deoxys, pathfinder, block_number).await);
+            self.next = block_number + 1;
+        }
+
+        Ok(alerts)
+    }
+
+    async fn compare<P: Provider>(
+        &self,
+        deoxys: &P,
+        pathfinder: &P,
+        block_number: u64,
+    ) -> Vec<Alert> {
+        let (_, divergences) =
+            compare_block(deoxys, pathfinder, block_number, &self.options.components).await;
+        divergences.into_iter().map(Alert::Divergence).collect()
+    }
+
+    /// Compares the reorganized blocks again once both nodes agree on them
+    async fn check_reorgs<P: Provider>(
+        &mut self,
+        deoxys: &P,
+        pathfinder: &P,
+        tip: u64,
+        reference_tip: u64,
+    ) -> anyhow::Result<Vec<Alert>> {
+        let window = self.options.reorg_window;
+        let now = Instant::now();
+        let mut alerts = vec![];
+
+        for (node, provider, tip) in [
+            (DEOXYS, deoxys, tip),
+            (PATHFINDER, pathfinder, reference_tip),
+        ] {
+            let hashes = block_hashes(provider, tip.saturating_sub(window - 1)..=tip).await?;
+
+            if let Some(replaced) = self.reorgs.update(node, &hashes, now) {
+                alerts.push(Alert::Reorg {
+                    node: node.to_string(),
+                    start: *replaced.start(),
+                    depth: replaced.end() - replaced.start() + 1,
+                });
+            }
+        }
+
+        if let Some(reorg) = self.reorgs.converged(&[DEOXYS, PATHFINDER]) {
+            log::info!("{reorg}");
+            alerts.push(Alert::ReorgConverged {
+                start: reorg.start,
+                depth: reorg.depth,
+                convergence_ms: reorg
+                    .convergence
+                    .iter()
+                    .map(|(node, duration)| (node.clone(), duration.as_millis() as u64))
+                    .collect(),
+            });
+
+            for block_number in reorg.start..(reorg.start + reorg.depth).min(self.next) {
+                log::info!("Comparing reorganized block {block_number}");
+                alerts.extend(self.compare(deoxys, pathfinder, block_number).await);
+            }
+        }
+
+        self.reorgs
+            .prune(tip.min(reference_tip).saturating_sub(window));
+
+        Ok(alerts)
+    }
+
+    ///
+    /// Polls the nodes forever, passing alerts to `on_alert`. Errors while polling, ex: a
+    /// node being restarted, are logged and polling resumes at the next interval.
+    ///
+    pub async fn run<P: Provider>(
+        &mut self,
+        deoxys: &P,
+        pathfinder: &P,
+        mut on_alert: impl FnMut(&Alert),
+    ) -> ! {
+        loop {
+            match self.poll(deoxys, pathfinder).await {
+                Ok(alerts) => alerts.iter().for_each(&mut on_alert),
+                Err(e) => log::warn!("Failed to poll nodes: {e}"),
+            }
+
+            sleep(self.options.poll_interval).await;
+        }
+    }
+}
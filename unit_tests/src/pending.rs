@@ -0,0 +1,216 @@
+use std::{collections::HashSet, fmt};
+
+use anyhow::bail;
+use serde_json::Value;
+use starknet_core::types::{
+    BlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes, PendingBlockWithTxHashes,
+};
+use starknet_providers::{Provider, ProviderError};
+
+use crate::{
+    constants::{DEOXYS, PATHFINDER},
+    diff::first_difference,
+};
+
+/// Receipt fields which only exist once a transaction is in a block
+const ACCEPTED_FIELDS: [&str; 2] = ["block_hash", "block_number"];
+
+///
+/// Where a node's pending block is attached, as seen by the other node.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentCheck {
+    pub node: &'static str,
+    pub parent_hash: FieldElement,
+    pub other: &'static str,
+    pub other_latest: u64,
+    /// Number of the parent on the other node, `None` if the other node does not know it
+    pub parent_number: Option<u64>,
+}
+
+impl ParentCheck {
+    pub fn is_other_latest(&self) -> bool {
+        self.parent_number == Some(self.other_latest)
+    }
+}
+
+impl fmt::Display for ParentCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pending parent 0x{:064x} is ",
+            self.node, self.parent_hash
+        )?;
+        match self.parent_number {
+            Some(n) if n == self.other_latest => write!(f, "{}'s latest block {n}", self.other),
+            Some(n) => write!(
+                f,
+                "{} block {n}, but its latest block is {}",
+                self.other, self.other_latest
+            ),
+            None => write!(f, "unknown to {}", self.other),
+        }
+    }
+}
+
+///
+/// Comparison of the pending blocks of Deoxys and Pathfinder, which legitimately differ as
+/// each node sees pending transactions at its own pace.
+///
+#[derive(Debug, Clone)]
+pub struct PendingComparison {
+    pub deoxys_transactions: usize,
+    pub pathfinder_transactions: usize,
+    pub shared_transactions: usize,
+    /// Shared transactions whose receipts differ, with the first difference
+    pub receipt_mismatches: Vec<(FieldElement, String)>,
+    pub parents: Vec<ParentCheck>,
+}
+
+impl PendingComparison {
+    /// Shared transactions over all pending transactions, `1.0` if both are empty
+    pub fn overlap(&self) -> f64 {
+        let union =
+            self.deoxys_transactions + self.pathfinder_transactions - self.shared_transactions;
+        if union == 0 {
+            1.0
+        } else {
+            self.shared_transactions as f64 / union as f64
+        }
+    }
+}
+
+impl fmt::Display for PendingComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Pending transactions: {DEOXYS} {}, {PATHFINDER} {}, shared {} ({:.0}% overlap)",
+            self.deoxys_transactions,
+            self.pathfinder_transactions,
+            self.shared_transactions,
+            self.overlap() * 100.0
+        )?;
+        for (hash, message) in self.receipt_mismatches.iter() {
+            writeln!(f, "Receipt of 0x{hash:064x} differs: {message}")?;
+        }
+        for parent in self.parents.iter() {
+            writeln!(f, "{parent}")?;
+        }
+        Ok(())
+    }
+}
+
+async fn pending_block<P: Provider>(
+    provider: &P,
+    name: &str,
+) -> anyhow::Result<PendingBlockWithTxHashes> {
+    match provider
+        .get_block_with_tx_hashes(BlockId::Tag(BlockTag::Pending))
+        .await?
+    {
+        MaybePendingBlockWithTxHashes::PendingBlock(block) => Ok(block),
+        MaybePendingBlockWithTxHashes::Block(block) => bail!(
+            "{name} returned block {} instead of a pending block",
+            block.block_number
+        ),
+    }
+}
+
+/// Receipt without the fields set once the transaction is accepted, which can happen
+/// between the two calls
+async fn pending_receipt<P: Provider>(
+    provider: &P,
+    hash: FieldElement,
+) -> Result<Value, ProviderError> {
+    let receipt = provider.get_transaction_receipt(hash).await?;
+
+    Ok(match serde_json::to_value(receipt) {
+        Ok(Value::Object(mut object)) => {
+            object.retain(|key, _| !ACCEPTED_FIELDS.contains(&key.as_str()));
+            Value::Object(object)
+        }
+        Ok(value) => value,
+        Err(e) => Value::String(e.to_string()),
+    })
+}
+
+///
+/// Transactions of `pending` which are also in `other`, in the order of `pending`.
+///
+pub fn shared_transactions(
+    pending: &PendingBlockWithTxHashes,
+    other: &PendingBlockWithTxHashes,
+) -> Vec<FieldElement> {
+    let transactions_other = other.transactions.iter().collect::<HashSet<_>>();
+
+    pending
+        .transactions
+        .iter()
+        .filter(|hash| transactions_other.contains(hash))
+        .copied()
+        .collect()
+}
+
+async fn check_parent<P: Provider>(
+    node: &'static str,
+    parent_hash: FieldElement,
+    other: &'static str,
+    other_provider: &P,
+) -> anyhow::Result<ParentCheck> {
+    let other_latest = other_provider.block_number().await?;
+    let parent_number = match other_provider
+        .get_block_with_tx_hashes(BlockId::Hash(parent_hash))
+        .await
+    {
+        Ok(MaybePendingBlockWithTxHashes::Block(block)) => Some(block.block_number),
+        Ok(MaybePendingBlockWithTxHashes::PendingBlock(_)) => None,
+        Err(ProviderError::StarknetError(_)) => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(ParentCheck {
+        node,
+        parent_hash,
+        other,
+        other_latest,
+        parent_number,
+    })
+}
+
+///
+/// Compares the pending blocks of Deoxys and Pathfinder: overlap of their transactions,
+/// receipts of the shared transactions, and whether each pending block is attached to the
+/// other node's latest block.
+///
+pub async fn compare_pending<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+) -> anyhow::Result<PendingComparison> {
+    let pending_deoxys = pending_block(deoxys, DEOXYS).await?;
+    let pending_pathfinder = pending_block(pathfinder, PATHFINDER).await?;
+
+    let shared = shared_transactions(&pending_deoxys, &pending_pathfinder);
+
+    let mut receipt_mismatches = vec![];
+    for hash in shared.iter() {
+        let receipt_deoxys = pending_receipt(deoxys, *hash).await?;
+        let receipt_pathfinder = pending_receipt(pathfinder, *hash).await?;
+
+        if let Some(difference) = first_difference(&receipt_deoxys, &receipt_pathfinder) {
+            receipt_mismatches.push((*hash, difference.to_string()));
+        }
+    }
+
+    let parents = vec![
+        check_parent(DEOXYS, pending_deoxys.parent_hash, PATHFINDER, pathfinder).await?,
+        check_parent(PATHFINDER, pending_pathfinder.parent_hash, DEOXYS, deoxys).await?,
+    ];
+
+    Ok(PendingComparison {
+        deoxys_transactions: pending_deoxys.transactions.len(),
+        pathfinder_transactions: pending_pathfinder.transactions.len(),
+        shared_transactions: shared.len(),
+        receipt_mismatches,
+        parents,
+    })
+}
@@ -0,0 +1,257 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::Context;
+use futures::{stream, StreamExt};
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+use starknet_core::types::{BlockId, FieldElement, StateDiff};
+use starknet_providers::Provider;
+
+use crate::drilldown::{query_entry, state_diff, state_entries, StateEntry};
+
+/// Number of state updates fetched ahead of the one being applied
+const PREFETCH: usize = 16;
+
+///
+/// State of the chain rebuilt by applying state diffs in order, independently of the
+/// node's own storage.
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconstructedState {
+    /// First block whose state diff has not been applied yet
+    pub next_block: u64,
+    /// Stored as a list as json objects only have string keys
+    #[serde(with = "entries")]
+    entries: BTreeMap<StateEntry, FieldElement>,
+    /// Entries written by the last applied block
+    #[serde(skip)]
+    last_written: Vec<StateEntry>,
+}
+
+mod entries {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        entries: &BTreeMap<StateEntry, FieldElement>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(entries.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<StateEntry, FieldElement>, D::Error> {
+        Ok(
+            Vec::<(StateEntry, FieldElement)>::deserialize(deserializer)?
+                .into_iter()
+                .collect(),
+        )
+    }
+}
+
+impl ReconstructedState {
+    /// Empty state, before genesis
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a state saved by [ReconstructedState::save], or starts from genesis if there is none
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let file = File::open(path)?;
+        serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("Invalid state {}", path.display()))
+    }
+
+    /// Written to a temporary file first so that an interruption never corrupts the state
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(&tmp, path).with_context(|| format!("Could not write {}", path.display()))
+    }
+
+    pub fn apply(&mut self, state_diff: &StateDiff) {
+        let written = state_entries(state_diff);
+
+        self.last_written = written.keys().copied().collect();
+        self.entries.extend(written);
+        self.next_block += 1;
+    }
+
+    pub fn get(&self, entry: &StateEntry) -> Option<FieldElement> {
+        self.entries.get(entry).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries written by the last applied block
+    pub fn last_written(&self) -> &[StateEntry] {
+        &self.last_written
+    }
+
+    /// Up to `amount` random entries which can be checked with point queries
+    pub fn sample(&self, amount: usize, rng: &mut StdRng) -> Vec<StateEntry> {
+        self.entries
+            .keys()
+            .filter(|entry| !matches!(entry, StateEntry::CompiledClassHash { .. }))
+            .copied()
+            .choose_multiple(rng, amount)
+    }
+}
+
+///
+/// Applies the state diffs of every block up to and including `to`.
+///
+pub async fn replay<P: Provider>(
+    provider: &P,
+    state: &mut ReconstructedState,
+    to: u64,
+) -> anyhow::Result<()> {
+    let state_diffs = stream::iter(state.next_block..=to)
+        .map(|block_number| async move {
+            (
+                block_number,
+                state_diff(provider, "node", block_number).await,
+            )
+        })
+        .buffered(PREFETCH);
+    let mut state_diffs = std::pin::pin!(state_diffs);
+
+    while let Some((block_number, state_diff)) = state_diffs.next().await {
+        let state_diff = state_diff.with_context(|| format!("State update {block_number}"))?;
+        state.apply(&state_diff);
+
+        if block_number % 1000 == 0 {
+            log::info!("Replayed block {block_number}, {} entries", state.len());
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// A point query whose answer differs from the reconstructed state.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointQueryMismatch {
+    pub block_number: u64,
+    pub entry: StateEntry,
+    pub expected: FieldElement,
+    /// `None` if the node did not find the contract
+    pub actual: Option<FieldElement>,
+}
+
+impl fmt::Display for PointQueryMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let actual = match self.actual {
+            Some(actual) => format!("0x{actual:064x}"),
+            None => String::from("contract not found"),
+        };
+
+        write!(
+            f,
+            "block {}: 0x{:064x} {}: expected 0x{:064x}, got {actual}",
+            self.block_number,
+            self.entry.contract(),
+            self.entry,
+            self.expected
+        )
+    }
+}
+
+///
+/// Checks `entries` with `getStorageAt`, `getNonce` and `getClassHashAt` at the last block
+/// applied to `state`.
+///
+pub async fn check_entries<P: Provider>(
+    provider: &P,
+    state: &ReconstructedState,
+    entries: &[StateEntry],
+) -> anyhow::Result<Vec<PointQueryMismatch>> {
+    let Some(block_number) = state.next_block.checked_sub(1) else {
+        return Ok(vec![]);
+    };
+    let block_id = BlockId::Number(block_number);
+
+    let mut mismatches = vec![];
+    for entry in entries {
+        let Some(expected) = state.get(entry) else {
+            continue;
+        };
+        if let StateEntry::CompiledClassHash { .. } = entry {
+            continue;
+        }
+
+        let actual = query_entry(provider, *entry, block_id).await?;
+        if actual != Some(expected) {
+            mismatches.push(PointQueryMismatch {
+                block_number,
+                entry: *entry,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(mismatches)
+}
+
+///
+/// Replays state diffs up to each of `blocks` and checks, at that block, the entries it
+/// wrote and `sample` random entries of the whole state. Fails if a block is already
+/// applied to `state`, as its state can no longer be rebuilt.
+///
+pub async fn reconstruct_and_check<P: Provider>(
+    provider: &P,
+    state: &mut ReconstructedState,
+    blocks: &[u64],
+    sample: usize,
+    seed: u64,
+) -> anyhow::Result<Vec<PointQueryMismatch>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut blocks = blocks.to_vec();
+    blocks.sort();
+    blocks.dedup();
+
+    if let Some(block_number) = blocks.first().filter(|&&block| block < state.next_block) {
+        anyhow::bail!(
+            "Block {block_number} is before the saved state, which is at block {}, use another \
+             state file to check it",
+            state.next_block
+        );
+    }
+
+    let mut mismatches = vec![];
+    for block_number in blocks {
+        replay(provider, state, block_number).await?;
+
+        let mut entries = state.last_written().to_vec();
+        entries.extend(state.sample(sample, &mut rng));
+        mismatches.extend(check_entries(provider, state, &entries).await?);
+    }
+
+    Ok(mismatches)
+}
@@ -0,0 +1,217 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+use starknet_core::types::{BlockId, FieldElement, MaybePendingBlockWithTxHashes};
+use starknet_providers::Provider;
+
+///
+/// Reorg seen by the [ReorgTracker], once all nodes agree again on the reorganized blocks.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reorg {
+    /// First block whose hash changed
+    pub start: u64,
+    /// Number of blocks replaced, on the node where the most blocks were replaced
+    pub depth: u64,
+    /// Time from the detection of the reorg until each node settled on the final blocks,
+    /// sorted by node name
+    pub convergence: Vec<(String, Duration)>,
+}
+
+impl fmt::Display for Reorg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Reorg of depth {} from block {}, converged after",
+            self.depth, self.start
+        )?;
+        for (node, duration) in self.convergence.iter() {
+            write!(f, " {node} {duration:?}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OngoingReorg {
+    start: u64,
+    end: u64,
+    detected_at: Instant,
+    /// Last time blocks of the reorg changed on each node
+    changed_at: HashMap<String, Instant>,
+}
+
+///
+/// Tracks the block hash of the most recent heights on each node, to detect blocks being
+/// replaced after they were compared.
+///
+#[derive(Debug, Clone, Default)]
+pub struct ReorgTracker {
+    /// Hash of each height, per node
+    hashes: BTreeMap<u64, BTreeMap<String, FieldElement>>,
+    ongoing: Option<OngoingReorg>,
+}
+
+impl ReorgTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn hash(&self, node: &str, block_number: u64) -> Option<FieldElement> {
+        self.hashes.get(&block_number)?.get(node).copied()
+    }
+
+    pub fn is_reorganizing(&self) -> bool {
+        self.ongoing.is_some()
+    }
+
+    ///
+    /// Records the latest hashes seen on `node`, and returns the blocks it replaced since
+    /// the previous update: from the first block whose hash changed up to the highest block
+    /// previously seen on that node. A tip lower than the previous one replaces the blocks
+    /// above it, even if they are later re-extended with the same hashes.
+    ///
+    pub fn update(
+        &mut self,
+        node: &str,
+        hashes: &[(u64, FieldElement)],
+        now: Instant,
+    ) -> Option<RangeInclusive<u64>> {
+        let tip = hashes.iter().map(|(block_number, _)| *block_number).max();
+        let previous_tip = self
+            .hashes
+            .iter()
+            .rev()
+            .find(|(_, hashes)| hashes.contains_key(node))
+            .map(|(block_number, _)| *block_number);
+        let dropped = match (tip, previous_tip) {
+            (Some(tip), Some(previous_tip)) if tip < previous_tip => Some(tip + 1),
+            _ => None,
+        };
+
+        let start = hashes
+            .iter()
+            .filter(|(block_number, hash)| {
+                self.hash(node, *block_number)
+                    .is_some_and(|previous| previous != *hash)
+            })
+            .map(|(block_number, _)| *block_number)
+            .chain(dropped)
+            .min();
+
+        let replaced = start.map(|start| {
+            let end = self
+                .hashes
+                .range(start..)
+                .filter(|(_, hashes)| hashes.contains_key(node))
+                .map(|(block_number, _)| *block_number)
+                .last()
+                .unwrap_or(start);
+            start..=end
+        });
+
+        // blocks above the new tip of a node no longer exist on it
+        if let Some(tip) = tip {
+            for (_, hashes) in self.hashes.range_mut(tip + 1..) {
+                hashes.remove(node);
+            }
+        }
+        for (block_number, hash) in hashes.iter() {
+            self.hashes
+                .entry(*block_number)
+                .or_default()
+                .insert(node.to_string(), *hash);
+        }
+
+        if let Some(replaced) = &replaced {
+            let ongoing = self.ongoing.get_or_insert_with(|| OngoingReorg {
+                start: *replaced.start(),
+                end: *replaced.end(),
+                detected_at: now,
+                changed_at: HashMap::new(),
+            });
+            ongoing.start = ongoing.start.min(*replaced.start());
+            ongoing.end = ongoing.end.max(*replaced.end());
+            ongoing.changed_at.insert(node.to_string(), now);
+        }
+
+        replaced
+    }
+
+    ///
+    /// Ends the ongoing reorg if all of `nodes` now have the same hashes for every
+    /// reorganized block.
+    ///
+    pub fn converged(&mut self, nodes: &[&str]) -> Option<Reorg> {
+        let ongoing = self.ongoing.as_ref()?;
+
+        let agree = (ongoing.start..=ongoing.end).all(|block_number| {
+            let hashes = nodes
+                .iter()
+                .map(|node| self.hash(node, block_number))
+                .collect::<Vec<_>>();
+            hashes
+                .iter()
+                .all(|hash| hash.is_some() && *hash == hashes[0])
+        });
+        if !agree {
+            return None;
+        }
+
+        let ongoing = self.ongoing.take()?;
+        let mut convergence = nodes
+            .iter()
+            .map(|node| {
+                let settled = ongoing
+                    .changed_at
+                    .get(*node)
+                    .copied()
+                    .unwrap_or(ongoing.detected_at);
+                (node.to_string(), settled - ongoing.detected_at)
+            })
+            .collect::<Vec<_>>();
+        convergence.sort();
+
+        Some(Reorg {
+            start: ongoing.start,
+            depth: ongoing.end - ongoing.start + 1,
+            convergence,
+        })
+    }
+
+    /// Forgets blocks below `block_number`, which are considered final, unless they are
+    /// part of the ongoing reorg
+    pub fn prune(&mut self, block_number: u64) {
+        let block_number = match &self.ongoing {
+            Some(ongoing) => block_number.min(ongoing.start),
+            None => block_number,
+        };
+        self.hashes = self.hashes.split_off(&block_number);
+    }
+}
+
+///
+/// Hashes of the blocks in `range` which exist on the node.
+///
+pub async fn block_hashes<P: Provider>(
+    provider: &P,
+    range: RangeInclusive<u64>,
+) -> anyhow::Result<Vec<(u64, FieldElement)>> {
+    let mut hashes = vec![];
+    for block_number in range {
+        match provider
+            .get_block_with_tx_hashes(BlockId::Number(block_number))
+            .await?
+        {
+            MaybePendingBlockWithTxHashes::Block(block) => {
+                hashes.push((block_number, block.block_hash))
+            }
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => break,
+        }
+    }
+    Ok(hashes)
+}
@@ -0,0 +1,310 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use futures::{stream::FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time::{sleep_until, Instant};
+
+use crate::{
+    batch::{BatchClient, Request},
+    bench::Stats,
+    diff::first_difference,
+    transport::{RawResponse, RpcObserver},
+};
+
+///
+/// Environment variable enabling traffic capture in the test fixtures. Must be set to the
+/// file the calls are appended to.
+///
+pub const CAPTURE_ENV: &str = "DITTO_CAPTURE";
+
+/// Mismatches listed in a [ReplayReport], the others are only counted
+const MAX_LISTED_MISMATCHES: usize = 20;
+
+///
+/// A single call of a capture, one json object per line, ex:
+/// `{"timestamp_ms":1700000000000,"method":"starknet_blockNumber","params":[],"response":{"result":50000}}`
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedCall {
+    /// Unix time at which the call was sent, in milliseconds
+    pub timestamp_ms: u64,
+    #[serde(flatten)]
+    pub request: Request,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<RawResponse>,
+}
+
+///
+/// Appends every call to a capture file, with or without its response.
+///
+pub struct CaptureRecorder {
+    file: Mutex<File>,
+    with_responses: bool,
+}
+
+impl CaptureRecorder {
+    pub fn new(path: impl AsRef<Path>, with_responses: bool) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Could not open {}", path.display()))?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+            with_responses,
+        })
+    }
+}
+
+impl RpcObserver for CaptureRecorder {
+    fn observe(&self, method: &str, params: &Value, response: &RawResponse) -> anyhow::Result<()> {
+        let call = CapturedCall {
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+            request: Request::new(method, params.clone()),
+            response: self.with_responses.then(|| response.clone()),
+        };
+        let mut line = serde_json::to_string(&call)?;
+        line.push('\n');
+
+        self.file.lock().unwrap().write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+///
+/// Calls of a capture file, ordered by timestamp.
+///
+pub fn load_capture(path: impl AsRef<Path>) -> anyhow::Result<Vec<CapturedCall>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+
+    let mut calls = BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(i, line)| {
+            serde_json::from_str(&line?)
+                .with_context(|| format!("Invalid call at {}:{}", path.display(), i + 1))
+        })
+        .collect::<anyhow::Result<Vec<CapturedCall>>>()?;
+    calls.sort_by_key(|call| call.timestamp_ms);
+
+    Ok(calls)
+}
+
+///
+/// Responses replayed calls are compared with.
+///
+#[derive(Debug, Clone)]
+pub enum Compare {
+    None,
+    /// The responses saved in the capture, calls without one are not compared
+    Recorded,
+    /// The responses of another node to the same calls
+    Reference(BatchClient),
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    /// Factor by which the original rate is multiplied, ex: 2.0 replays twice as fast
+    pub speed: f64,
+    /// Calls in flight above which new calls are delayed, the delay still being counted
+    /// in their latency
+    pub max_in_flight: usize,
+    pub compare: Compare,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            max_in_flight: 1000,
+            compare: Compare::None,
+        }
+    }
+}
+
+///
+/// A replayed call whose response differs from the expected one.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Index of the call in the capture
+    pub index: usize,
+    pub method: String,
+    pub difference: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} {}: {}", self.index, self.method, self.difference)
+    }
+}
+
+///
+/// Latency of the replayed calls by method, measured from the time each call was
+/// scheduled, and the responses which differ from the expected ones.
+///
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    pub stats: BTreeMap<String, Stats>,
+    pub compared: u64,
+    pub mismatches: Vec<Mismatch>,
+}
+
+type Replayed = (
+    usize,
+    Duration,
+    anyhow::Result<RawResponse>,
+    Option<anyhow::Result<RawResponse>>,
+);
+
+impl ReplayReport {
+    fn record(&mut self, calls: &[CapturedCall], (index, latency, response, expected): Replayed) {
+        let method = &calls[index].request.method;
+        let stats = self.stats.entry(method.clone()).or_default();
+
+        let response = match response {
+            Ok(response @ RawResponse::Success { .. }) => {
+                stats.record(latency);
+                response
+            }
+            Ok(response @ RawResponse::Error { .. }) => {
+                stats.record_error();
+                response
+            }
+            Err(e) => {
+                log::debug!("{e:#}");
+                stats.record_error();
+                return;
+            }
+        };
+
+        let expected = match expected {
+            Some(Ok(expected)) => expected,
+            Some(Err(e)) => {
+                log::warn!("No reference response to call #{index}: {e:#}");
+                return;
+            }
+            None => return,
+        };
+
+        self.compared += 1;
+        // expected response on the left, replayed one on the right
+        let to_json = |response: &RawResponse| serde_json::to_value(response).unwrap_or_default();
+        if let Some(difference) = first_difference(&to_json(&expected), &to_json(&response)) {
+            self.mismatches.push(Mismatch {
+                index,
+                method: method.clone(),
+                difference: difference.to_string(),
+            });
+        }
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<40} {}", "method", Stats::header())?;
+        for (method, stats) in self.stats.iter() {
+            writeln!(f, "{method:<40} {stats}")?;
+        }
+
+        if self.compared > 0 {
+            writeln!(
+                f,
+                "{} of {} compared responses differ",
+                self.mismatches.len(),
+                self.compared
+            )?;
+            for mismatch in self.mismatches.iter().take(MAX_LISTED_MISMATCHES) {
+                writeln!(f, "  {mismatch}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Sends the captured calls to `client` on their original schedule, scaled by
+/// `options.speed`, whether or not previous calls were answered.
+///
+pub async fn replay(
+    client: &BatchClient,
+    calls: &[CapturedCall],
+    options: &ReplayOptions,
+) -> anyhow::Result<ReplayReport> {
+    anyhow::ensure!(
+        options.speed > 0.0,
+        "Replay speed must be positive, got {}",
+        options.speed
+    );
+
+    let mut report = ReplayReport::default();
+    let Some(first) = calls.first() else {
+        return Ok(report);
+    };
+
+    let start = Instant::now();
+    let mut in_flight = FuturesUnordered::new();
+
+    for (index, call) in calls.iter().enumerate() {
+        let offset = Duration::from_millis(call.timestamp_ms.saturating_sub(first.timestamp_ms));
+        let intended = start + offset.div_f64(options.speed);
+
+        loop {
+            tokio::select! {
+                _ = sleep_until(intended) => break,
+                Some(done) = in_flight.next(), if !in_flight.is_empty() => report.record(calls, done),
+            }
+        }
+        while in_flight.len() >= options.max_in_flight.max(1) {
+            if let Some(done) = in_flight.next().await {
+                report.record(calls, done);
+            }
+        }
+
+        in_flight.push(async move {
+            let replayed = async {
+                let response = client.call(&call.request).await;
+                (response, intended.elapsed())
+            };
+
+            // the reference node is called at the same time, so that both nodes answer
+            // for the same chain state and the replay keeps its pace
+            let ((response, latency), expected) = match &options.compare {
+                Compare::None => (replayed.await, None),
+                Compare::Recorded => (replayed.await, call.response.clone().map(Ok)),
+                Compare::Reference(reference) => {
+                    let (replayed, expected) =
+                        tokio::join!(replayed, reference.call(&call.request));
+                    (replayed, Some(expected))
+                }
+            };
+            (index, latency, response, expected)
+        });
+    }
+
+    while let Some(done) = in_flight.next().await {
+        report.record(calls, done);
+    }
+
+    let elapsed = start.elapsed();
+    for stats in report.stats.values_mut() {
+        stats.elapsed = elapsed;
+    }
+    report.mismatches.sort_by_key(|mismatch| mismatch.index);
+
+    Ok(report)
+}
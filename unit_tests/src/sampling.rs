@@ -0,0 +1,147 @@
+use std::{fmt, ops::RangeInclusive};
+
+use futures::{stream, StreamExt};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use starknet_core::types::BlockId;
+use starknet_providers::Provider;
+
+use crate::eras::find_eras;
+
+///
+/// Environment variable overriding the sampling seed, to reproduce a failing test.
+///
+pub const SEED_ENV: &str = "DITTO_SEED";
+
+/// Candidates drawn for every block sampled with [Strategy::BusyBlocks]
+const BUSY_CANDIDATES: usize = 8;
+
+///
+/// How blocks are picked by a [Sampler].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Uniformly from genesis up to the current tip
+    Uniform,
+    /// The same number of blocks from each Starknet version
+    StratifiedByVersion,
+    /// Uniformly, weighted by the number of transactions in the block
+    BusyBlocks,
+    /// Uniformly from the `depth` blocks below the current tip
+    RecentTip { depth: u64 },
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uniform => write!(f, "uniform"),
+            Self::StratifiedByVersion => write!(f, "stratified by version"),
+            Self::BusyBlocks => write!(f, "busy blocks"),
+            Self::RecentTip { depth } => write!(f, "recent tip (depth {depth})"),
+        }
+    }
+}
+
+///
+/// Seeded block sampler. The seed is logged on creation so that a failing test can be
+/// re-run on the same blocks with `DITTO_SEED=<seed>`.
+///
+pub struct Sampler {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler {
+    /// Seed from `DITTO_SEED`, or a random one
+    pub fn new() -> Self {
+        let seed = match std::env::var(SEED_ENV) {
+            Ok(seed) => seed
+                .parse()
+                .unwrap_or_else(|_| panic!("{SEED_ENV} must be a u64, got '{seed}'")),
+            Err(_) => rand::thread_rng().gen(),
+        };
+
+        log::info!("Sampling blocks with seed {seed}, run with {SEED_ENV}={seed} to reproduce");
+        Self::with_seed(seed)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///
+    /// `count` blocks drawn uniformly from `range`, sorted and without duplicates.
+    ///
+    pub fn uniform(&mut self, range: RangeInclusive<u64>, count: usize) -> Vec<u64> {
+        let mut blocks = (0..count)
+            .map(|_| self.rng.gen_range(range.clone()))
+            .collect::<Vec<_>>();
+        blocks.sort();
+        blocks.dedup();
+        blocks
+    }
+
+    ///
+    /// Up to `count` blocks picked with `strategy`, sorted and without duplicates. The tip
+    /// is the provider's latest block.
+    ///
+    pub async fn sample<P: Provider>(
+        &mut self,
+        provider: &P,
+        strategy: Strategy,
+        count: usize,
+    ) -> anyhow::Result<Vec<u64>> {
+        let tip = provider.block_number().await?;
+
+        let mut blocks = match strategy {
+            Strategy::Uniform => self.uniform(0..=tip, count),
+            Strategy::RecentTip { depth } => self.uniform(tip.saturating_sub(depth)..=tip, count),
+            Strategy::StratifiedByVersion => {
+                let eras = find_eras(provider, 0..=tip).await?;
+                let per_era = count.div_ceil(eras.len());
+
+                eras.iter()
+                    .flat_map(|era| self.uniform(era.blocks(), per_era))
+                    .collect()
+            }
+            Strategy::BusyBlocks => {
+                let candidates = self.uniform(0..=tip, count * BUSY_CANDIDATES);
+                let counts = stream::iter(candidates)
+                    .map(|block_number| async move {
+                        provider
+                            .get_block_transaction_count(BlockId::Number(block_number))
+                            .await
+                            .map(|count| (block_number, count))
+                    })
+                    .buffered(16)
+                    .collect::<Vec<_>>()
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                counts
+                    .choose_multiple_weighted(&mut self.rng, count, |(_, n)| *n as f64 + 1.0)?
+                    .map(|(block_number, _)| *block_number)
+                    .collect()
+            }
+        };
+
+        blocks.sort();
+        blocks.dedup();
+        log::info!("Sampled blocks ({strategy}): {blocks:?}");
+
+        Ok(blocks)
+    }
+}
@@ -0,0 +1,448 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::Path,
+};
+
+use anyhow::{anyhow, bail, Context};
+use jsonschema::{Draft, JSONSchema};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    constants::{SPEC_0_5_1, SPEC_0_6_0},
+    transport::{RawError, RawResponse, RpcObserver},
+};
+
+///
+/// Directory the OpenRPC specs are fetched to, with one sub-directory per spec version.
+///
+/// Specs are taken as-is from [starknet-specs](https://github.com/starkware-libs/starknet-specs),
+/// see `specs/fetch.sh`.
+///
+pub const SPEC_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/specs");
+
+/// Spec versions fetched by `specs/fetch.sh`, responses of nodes implementing them are
+/// always validated
+pub const SUPPORTED_VERSIONS: [&str; 2] = [SPEC_0_5_1, SPEC_0_6_0];
+
+/// Spec documents making up a spec version, along with the key used to reference them
+const SPEC_FILES: [(&str, &str); 3] = [
+    ("api", "starknet_api_openrpc.json"),
+    ("trace", "starknet_trace_api_openrpc.json"),
+    ("write", "starknet_write_api.json"),
+];
+
+/// Generic JSON-RPC errors, which can be returned by any method
+const JSON_RPC_ERRORS: [i64; 5] = [-32700, -32600, -32601, -32602, -32603];
+
+#[derive(Debug, Clone)]
+pub struct SpecParam {
+    pub name: String,
+    pub required: bool,
+    pub schema: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpecError {
+    pub name: String,
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpecMethod {
+    pub name: String,
+    pub params: Vec<SpecParam>,
+    pub result: Value,
+    pub errors: Vec<SpecError>,
+}
+
+///
+/// Starknet OpenRPC spec for a given version, merged from the api, trace and write documents.
+///
+/// All `$ref`s are rewritten to point into a single `definitions` object so that schemas
+/// can be resolved regardless of the document they were declared in.
+///
+#[derive(Debug, Clone)]
+pub struct OpenRpcSpec {
+    pub version: String,
+    pub methods: Vec<SpecMethod>,
+    definitions: Value,
+}
+
+impl OpenRpcSpec {
+    pub fn load(version: &str) -> anyhow::Result<Self> {
+        Self::load_from(Path::new(SPEC_DIR).join(version), version)
+    }
+
+    pub fn load_from(dir: impl AsRef<Path>, version: &str) -> anyhow::Result<Self> {
+        let mut documents = vec![];
+        for (key, file) in SPEC_FILES {
+            let path = dir.as_ref().join(file);
+            let content = fs::read_to_string(&path).with_context(|| {
+                format!(
+                    "Could not read spec file {}, see specs/fetch.sh",
+                    path.display()
+                )
+            })?;
+            let document = serde_json::from_str::<Value>(&content)
+                .with_context(|| format!("Invalid spec file {}", path.display()))?;
+
+            documents.push(rewrite_refs(document, key));
+        }
+
+        let mut definitions = Map::new();
+        for ((key, _), document) in SPEC_FILES.iter().zip(documents.iter()) {
+            let components = document.get("components").cloned().unwrap_or(json!({}));
+            definitions.insert(key.to_string(), components);
+        }
+        let definitions = Value::Object(definitions);
+
+        let mut methods = vec![];
+        for document in documents.iter() {
+            for method in document["methods"].as_array().into_iter().flatten() {
+                methods.push(parse_method(method, &definitions)?);
+            }
+        }
+
+        Ok(Self {
+            version: version.to_string(),
+            methods,
+            definitions,
+        })
+    }
+
+    pub fn method(&self, name: &str) -> Option<&SpecMethod> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+
+    ///
+    /// Whether `schema` references the schema called `name`, either directly or through
+    /// other references. Ex: the `filter` param of `starknet_getEvents` references `BLOCK_ID`.
+    ///
+    pub fn references(&self, schema: &Value, name: &str) -> bool {
+        self.references_visit(schema, name, &mut HashSet::new())
+    }
+
+    fn references_visit<'a>(
+        &'a self,
+        schema: &'a Value,
+        name: &str,
+        visited: &mut HashSet<&'a str>,
+    ) -> bool {
+        match schema {
+            Value::Object(object) => object.iter().any(|(key, value)| match value {
+                Value::String(reference) if key == "$ref" => {
+                    reference.rsplit('/').next() == Some(name)
+                        || (visited.insert(reference)
+                            && reference
+                                .strip_prefix("#/definitions")
+                                .and_then(|pointer| self.definitions.pointer(pointer))
+                                .is_some_and(|schema| self.references_visit(schema, name, visited)))
+                }
+                value => self.references_visit(value, name, visited),
+            }),
+            Value::Array(array) => array
+                .iter()
+                .any(|value| self.references_visit(value, name, visited)),
+            _ => false,
+        }
+    }
+
+    ///
+    /// Compiles a schema taken from this spec into a validator.
+    ///
+    pub fn compile(&self, schema: &Value) -> anyhow::Result<JSONSchema> {
+        // wrapping the schema in `allOf` keeps `definitions` at the root even if the
+        // schema itself is a `$ref`, whose siblings are ignored in draft 7
+        let root = json!({
+            "definitions": self.definitions,
+            "allOf": [schema],
+        });
+
+        let schema = JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .compile(&root)
+            .map_err(|e| anyhow!("Invalid schema: {e}"))?;
+
+        Ok(schema)
+    }
+}
+
+///
+/// Rewrites `$ref`s in a spec document so that they point into the merged `definitions`.
+///
+/// `oneOf` is also relaxed into `anyOf`: several unions in the spec have overlapping
+/// variants (ex: a block also satisfies the pending block schema), which would make any
+/// valid response fail with `oneOf`.
+///
+fn rewrite_refs(value: Value, key: &str) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(k, v)| match v {
+                    Value::String(reference) if k == "$ref" => {
+                        (k, Value::String(rewrite_ref(&reference, key)))
+                    }
+                    v if k == "oneOf" => (String::from("anyOf"), rewrite_refs(v, key)),
+                    v => (k, rewrite_refs(v, key)),
+                })
+                .collect(),
+        ),
+        Value::Array(array) => Value::Array(
+            array
+                .into_iter()
+                .map(|value| rewrite_refs(value, key))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn rewrite_ref(reference: &str, key: &str) -> String {
+    let Some((file, pointer)) = reference.split_once('#') else {
+        return reference.to_string();
+    };
+
+    let document = if file.is_empty() {
+        Some(key)
+    } else {
+        let file = file.rsplit('/').next().unwrap_or(file);
+        SPEC_FILES
+            .iter()
+            .find(|(_, name)| *name == file)
+            .map(|(key, _)| *key)
+    };
+
+    match (document, pointer.strip_prefix("/components")) {
+        (Some(document), Some(pointer)) => format!("#/definitions/{document}{pointer}"),
+        _ => reference.to_string(),
+    }
+}
+
+/// Follows `$ref`s until a concrete value is reached
+fn resolve<'a>(value: &'a Value, definitions: &'a Value) -> anyhow::Result<&'a Value> {
+    let mut value = value;
+    while let Some(reference) = value.get("$ref").and_then(Value::as_str) {
+        value = reference
+            .strip_prefix("#/definitions")
+            .and_then(|pointer| definitions.pointer(pointer))
+            .ok_or_else(|| anyhow!("Unresolved reference {reference}"))?;
+    }
+    Ok(value)
+}
+
+fn parse_method(method: &Value, definitions: &Value) -> anyhow::Result<SpecMethod> {
+    let method = resolve(method, definitions)?;
+    let Some(name) = method["name"].as_str() else {
+        bail!("Method without a name: {method}");
+    };
+
+    let mut params = vec![];
+    for param in method["params"].as_array().into_iter().flatten() {
+        let param = resolve(param, definitions)?;
+        params.push(SpecParam {
+            name: param["name"].as_str().unwrap_or_default().to_string(),
+            required: param["required"].as_bool().unwrap_or(false),
+            schema: param["schema"].clone(),
+        });
+    }
+
+    let result = match method.get("result") {
+        Some(result) => resolve(result, definitions)?["schema"].clone(),
+        None => json!({}),
+    };
+
+    let mut errors = vec![];
+    for error in method["errors"].as_array().into_iter().flatten() {
+        let name = error["$ref"]
+            .as_str()
+            .and_then(|reference| reference.rsplit('/').next())
+            .unwrap_or_default()
+            .to_string();
+        let error = resolve(error, definitions)?;
+        let Some(code) = error["code"].as_i64() else {
+            bail!("Error {name} without a code: {error}");
+        };
+
+        errors.push(SpecError {
+            name,
+            code,
+            message: error["message"].as_str().unwrap_or_default().to_string(),
+            data: error.get("data").cloned(),
+        });
+    }
+
+    Ok(SpecMethod {
+        name: name.to_string(),
+        params,
+        result,
+        errors,
+    })
+}
+
+///
+/// Schema violation in an RPC response. `pointer` is a JSON pointer into the response
+/// object, ex: `/result/transactions/0/version`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub method: String,
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at '{}': {}", self.method, self.pointer, self.message)
+    }
+}
+
+struct CompiledError {
+    spec: SpecError,
+    data: Option<JSONSchema>,
+}
+
+struct CompiledMethod {
+    result: JSONSchema,
+    errors: Vec<CompiledError>,
+}
+
+///
+/// Validates raw RPC responses against the result and error schemas of an [OpenRpcSpec].
+///
+pub struct SpecValidator {
+    version: String,
+    methods: HashMap<String, CompiledMethod>,
+}
+
+impl SpecValidator {
+    pub fn new(spec: &OpenRpcSpec) -> anyhow::Result<Self> {
+        let mut methods = HashMap::new();
+
+        for method in spec.methods.iter() {
+            let result = spec
+                .compile(&method.result)
+                .with_context(|| format!("Result schema of {}", method.name))?;
+
+            let mut errors = vec![];
+            for error in method.errors.iter() {
+                let data = match &error.data {
+                    Some(schema) => Some(spec.compile(schema).with_context(|| {
+                        format!("Data schema of {} in {}", error.name, method.name)
+                    })?),
+                    None => None,
+                };
+                errors.push(CompiledError {
+                    spec: error.clone(),
+                    data,
+                });
+            }
+
+            methods.insert(method.name.clone(), CompiledMethod { result, errors });
+        }
+
+        Ok(Self {
+            version: spec.version.clone(),
+            methods,
+        })
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn validate(&self, method: &str, response: &RawResponse) -> Vec<Violation> {
+        let violation = |pointer: String, message: String| Violation {
+            method: method.to_string(),
+            pointer,
+            message,
+        };
+
+        let Some(compiled) = self.methods.get(method) else {
+            return vec![violation(
+                String::new(),
+                format!("method is not part of spec {}", self.version),
+            )];
+        };
+
+        match response {
+            RawResponse::Success { result } => match compiled.result.validate(result) {
+                Ok(()) => vec![],
+                Err(errors) => errors
+                    .map(|e| violation(format!("/result{}", e.instance_path), e.to_string()))
+                    .collect(),
+            },
+            RawResponse::Error { error } => {
+                Self::validate_error(&compiled.errors, error, violation)
+            }
+        }
+    }
+
+    fn validate_error(
+        expected: &[CompiledError],
+        error: &RawError,
+        violation: impl Fn(String, String) -> Violation,
+    ) -> Vec<Violation> {
+        if JSON_RPC_ERRORS.contains(&error.code) {
+            return vec![];
+        }
+
+        let Some(expected) = expected.iter().find(|e| e.spec.code == error.code) else {
+            return vec![violation(
+                String::from("/error/code"),
+                format!("error code {} is not listed for this method", error.code),
+            )];
+        };
+
+        let mut violations = vec![];
+        if expected.spec.message != error.message {
+            violations.push(violation(
+                String::from("/error/message"),
+                format!(
+                    "expected {:?} for {}, got {:?}",
+                    expected.spec.message, expected.spec.name, error.message
+                ),
+            ));
+        }
+
+        match (&expected.data, &error.data) {
+            (Some(schema), Some(data)) => {
+                if let Err(errors) = schema.validate(data) {
+                    violations.extend(errors.map(|e| {
+                        violation(format!("/error/data{}", e.instance_path), e.to_string())
+                    }));
+                }
+            }
+            (Some(_), None) => violations.push(violation(
+                String::from("/error/data"),
+                format!("missing data for {}", expected.spec.name),
+            )),
+            _ => {}
+        }
+
+        violations
+    }
+}
+
+impl RpcObserver for SpecValidator {
+    fn observe(&self, method: &str, _: &Value, response: &RawResponse) -> anyhow::Result<()> {
+        let violations = self.validate(method, response);
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            let violations = violations
+                .iter()
+                .map(Violation::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+            Err(anyhow!(
+                "Response does not match spec {}:\n{violations}",
+                self.version
+            ))
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::{
+    fmt, fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use starknet_core::types::{BlockId, FieldElement, MaybePendingBlockWithTxHashes};
+use starknet_providers::Provider;
+
+use crate::{
+    constants::{DEOXYS, PATHFINDER},
+    diff::{first_difference, sort_arrays},
+    equivalence::{serialize, CallResult},
+};
+
+/// Number of blocks compared between two checkpoint writes
+const CHECKPOINT_INTERVAL: u64 = 100;
+/// Number of receipts compared concurrently inside a block
+const RECEIPT_CONCURRENCY: usize = 16;
+/// Json values longer than this are truncated in divergence messages
+const MAX_VALUE_LEN: usize = 200;
+
+///
+/// Part of a block compared by the [sweep].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Component {
+    /// `getBlockWithTxs` and `getBlockTransactionCount`
+    Block,
+    /// `getTransactionReceipt` for every transaction of the block
+    Receipts,
+    /// `getStateUpdate`
+    StateUpdate,
+    /// `traceBlockTransactions`
+    Traces,
+}
+
+impl Component {
+    pub const ALL: [Self; 4] = [Self::Block, Self::Receipts, Self::StateUpdate, Self::Traces];
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::Receipts => write!(f, "receipts"),
+            Self::StateUpdate => write!(f, "state_update"),
+            Self::Traces => write!(f, "traces"),
+        }
+    }
+}
+
+impl FromStr for Component {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL
+            .into_iter()
+            .find(|component| component.to_string() == s)
+        {
+            Some(component) => Ok(component),
+            None => bail!(
+                "Unknown component '{s}', expected one of block, receipts, state_update, traces"
+            ),
+        }
+    }
+}
+
+///
+/// A component of a block which differs between Deoxys and Pathfinder.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub block_number: u64,
+    pub component: Component,
+    pub message: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "block {}: {}: {}",
+            self.block_number, self.component, self.message
+        )
+    }
+}
+
+///
+/// Progress of a [sweep], saved to disk so that an interrupted sweep can be resumed. Once
+/// the sweep is done, this is also its report.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub from: u64,
+    pub to: u64,
+    /// First block which has not been compared yet
+    pub next: u64,
+    pub divergences: Vec<Divergence>,
+}
+
+impl Checkpoint {
+    pub fn new(range: &RangeInclusive<u64>) -> Self {
+        Self {
+            from: *range.start(),
+            to: *range.end(),
+            next: *range.start(),
+            divergences: vec![],
+        }
+    }
+
+    ///
+    /// Loads the checkpoint at `path`, or starts a new one if there is none. Fails if the
+    /// checkpoint was saved by a sweep over another range.
+    ///
+    pub fn load(path: impl AsRef<Path>, range: &RangeInclusive<u64>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let Some(checkpoint) = Self::saved(path)? else {
+            return Ok(Self::new(range));
+        };
+
+        if checkpoint.from != *range.start() || checkpoint.to != *range.end() {
+            bail!(
+                "Checkpoint {} is for blocks {}..={}, not {}..={}",
+                path.display(),
+                checkpoint.from,
+                checkpoint.to,
+                range.start(),
+                range.end()
+            );
+        }
+
+        Ok(checkpoint)
+    }
+
+    /// Checkpoint saved at `path`, `None` if no sweep was started there
+    pub fn saved(path: impl AsRef<Path>) -> anyhow::Result<Option<Self>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map(Some)
+            .with_context(|| format!("Invalid checkpoint {}", path.display()))
+    }
+
+    /// Written to a temporary file first so that an interruption never corrupts the checkpoint
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path).with_context(|| format!("Could not write {}", path.display()))
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next > self.to
+    }
+
+    /// Blocks with at least one divergence, in order
+    pub fn divergent_blocks(&self) -> Vec<u64> {
+        let mut blocks = self
+            .divergences
+            .iter()
+            .map(|divergence| divergence.block_number)
+            .collect::<Vec<_>>();
+        blocks.dedup();
+        blocks
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let compared = self.next - self.from;
+        let divergent_blocks = self.divergent_blocks();
+
+        writeln!(
+            f,
+            "Compared {compared} blocks out of {}..={}, {} divergent blocks",
+            self.from,
+            self.to,
+            divergent_blocks.len()
+        )?;
+        for divergence in self.divergences.iter() {
+            writeln!(f, "{divergence}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SweepOptions {
+    pub components: Vec<Component>,
+    /// Number of blocks compared concurrently
+    pub concurrency: usize,
+    /// Where progress is saved, `None` to keep it in memory only
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl Default for SweepOptions {
+    fn default() -> Self {
+        Self {
+            components: Component::ALL.to_vec(),
+            concurrency: 10,
+            checkpoint: None,
+        }
+    }
+}
+
+///
+/// Compares every block in `range` between Deoxys and Pathfinder, without stopping at the
+/// first divergence. If a checkpoint path is set, the sweep resumes from where a previous
+/// sweep over the same range stopped.
+///
+pub async fn sweep<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    range: RangeInclusive<u64>,
+    options: &SweepOptions,
+) -> anyhow::Result<Checkpoint> {
+    let mut checkpoint = match &options.checkpoint {
+        Some(path) => Checkpoint::load(path, &range)?,
+        None => Checkpoint::new(&range),
+    };
+    let save = |checkpoint: &Checkpoint| match &options.checkpoint {
+        Some(path) => checkpoint.save(path),
+        None => Ok(()),
+    };
+
+    if checkpoint.next > checkpoint.from {
+        log::info!("Resuming sweep from block {}", checkpoint.next);
+    }
+
+    let blocks = stream::iter(checkpoint.next..=checkpoint.to)
+        .map(|block_number| compare_block(deoxys, pathfinder, block_number, &options.components))
+        .buffered(options.concurrency.max(1));
+    let mut blocks = std::pin::pin!(blocks);
+
+    while let Some((block_number, divergences)) = blocks.next().await {
+        for divergence in divergences.iter() {
+            log::warn!("{divergence}");
+        }
+
+        checkpoint.divergences.extend(divergences);
+        checkpoint.next = block_number + 1;
+
+        if (checkpoint.next - checkpoint.from) % CHECKPOINT_INTERVAL == 0 {
+            save(&checkpoint)?;
+        }
+    }
+
+    save(&checkpoint)?;
+    Ok(checkpoint)
+}
+
+///
+/// Compares `components` of a single block between Deoxys and Pathfinder.
+///
+pub async fn compare_block<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    block_number: u64,
+    components: &[Component],
+) -> (u64, Vec<Divergence>) {
+    let block_id = BlockId::Number(block_number);
+    let mut divergences = vec![];
+
+    for component in components.iter().copied() {
+        let messages: Vec<String> = match component {
+            Component::Block => [
+                compare(
+                    "getBlockWithTxs",
+                    serialize(deoxys.get_block_with_txs(block_id).await),
+                    serialize(pathfinder.get_block_with_txs(block_id).await),
+                ),
+                compare(
+                    "getBlockTransactionCount",
+                    serialize(deoxys.get_block_transaction_count(block_id).await),
+                    serialize(pathfinder.get_block_transaction_count(block_id).await),
+                ),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            Component::Receipts => compare_receipts(deoxys, pathfinder, block_id).await,
+            Component::StateUpdate => compare(
+                "getStateUpdate",
+                unordered(serialize(deoxys.get_state_update(block_id).await)),
+                unordered(serialize(pathfinder.get_state_update(block_id).await)),
+            )
+            .into_iter()
+            .collect(),
+            Component::Traces => compare(
+                "traceBlockTransactions",
+                unordered(serialize(deoxys.trace_block_transactions(block_id).await)),
+                unordered(serialize(
+                    pathfinder.trace_block_transactions(block_id).await,
+                )),
+            )
+            .into_iter()
+            .collect(),
+        };
+
+        divergences.extend(messages.into_iter().map(|message| Divergence {
+            block_number,
+            component,
+            message,
+        }));
+    }
+
+    (block_number, divergences)
+}
+
+/// Receipts of the transactions listed by Deoxys, a different list is reported as a block divergence
+async fn compare_receipts<P: Provider>(
+    deoxys: &P,
+    pathfinder: &P,
+    block_id: BlockId,
+) -> Vec<String> {
+    let hashes = match deoxys.get_block_with_tx_hashes(block_id).await {
+        Ok(MaybePendingBlockWithTxHashes::Block(block)) => block.transactions,
+        Ok(MaybePendingBlockWithTxHashes::PendingBlock(_)) => {
+            return vec![format!("{DEOXYS} returned a pending block")]
+        }
+        Err(e) => return vec![format!("{DEOXYS} could not list transactions: {e}")],
+    };
+
+    stream::iter(hashes)
+        .map(|hash: FieldElement| async move {
+            compare(
+                &format!("getTransactionReceipt(0x{hash:064x})"),
+                serialize(deoxys.get_transaction_receipt(hash).await),
+                serialize(pathfinder.get_transaction_receipt(hash).await),
+            )
+        })
+        .buffered(RECEIPT_CONCURRENCY)
+        .filter_map(|message| async move { message })
+        .collect()
+        .await
+}
+
+/// Sorts the arrays of a successful response, as nodes are free to list storage diffs,
+/// nonces and the like in any order, see [crate::bisect]
+fn unordered(result: CallResult) -> CallResult {
+    result.map(|mut value| {
+        sort_arrays(&mut value);
+        value
+    })
+}
+
+fn compare(method: &str, deoxys: CallResult, pathfinder: CallResult) -> Option<String> {
+    match (deoxys, pathfinder) {
+        (Ok(deoxys), Ok(pathfinder)) => first_difference(&deoxys, &pathfinder).map(|d| {
+            format!(
+                "{method} {}: {DEOXYS} has {}, {PATHFINDER} has {}",
+                d.pointer,
+                truncate(d.left.to_string()),
+                truncate(d.right.to_string())
+            )
+        }),
+        (Err(deoxys), Err(pathfinder)) if deoxys == pathfinder => None,
+        (Err(deoxys), Err(pathfinder)) => Some(format!(
+            "{method}: {DEOXYS} failed with {deoxys}, {PATHFINDER} failed with {pathfinder}"
+        )),
+        (Err(e), Ok(_)) => Some(format!("{method}: only {DEOXYS} failed: {e}")),
+        (Ok(_), Err(e)) => Some(format!("{method}: only {PATHFINDER} failed: {e}")),
+    }
+}
+
+fn truncate(mut value: String) -> String {
+    if value.len() > MAX_VALUE_LEN {
+        let end = (0..=MAX_VALUE_LEN)
+            .rev()
+            .find(|i| value.is_char_boundary(*i))
+            .unwrap_or(0);
+        value.truncate(end);
+        value.push('…');
+    }
+    value
+}
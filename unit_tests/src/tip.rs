@@ -0,0 +1,216 @@
+use std::{collections::HashMap, fmt, future::Future, time::Duration};
+
+use anyhow::{anyhow, bail};
+use starknet_core::types::{BlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes};
+use starknet_providers::{Provider, ProviderError};
+use tokio::time::{sleep, Instant};
+
+#[derive(Debug, Clone)]
+pub struct TipOptions {
+    /// How long to wait for the nodes to agree on a common block
+    pub budget: Duration,
+    pub poll_interval: Duration,
+}
+
+impl Default for TipOptions {
+    fn default() -> Self {
+        Self {
+            budget: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+///
+/// Most recent block which all nodes have, with the same hash.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlignedTip {
+    pub block_number: u64,
+    pub block_hash: FieldElement,
+    /// Number of blocks each node is ahead of the aligned block, sorted by node name
+    pub lag: Vec<(String, u64)>,
+    pub attempts: u32,
+}
+
+impl fmt::Display for AlignedTip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Aligned on block {} (0x{:064x}) after {} attempts, tip lag:",
+            self.block_number, self.block_hash, self.attempts
+        )?;
+        for (name, lag) in self.lag.iter() {
+            write!(f, " {name} +{lag}")?;
+        }
+        Ok(())
+    }
+}
+
+async fn block_hash<P: Provider>(
+    provider: &P,
+    block_number: u64,
+) -> Result<Option<FieldElement>, ProviderError> {
+    match provider
+        .get_block_with_tx_hashes(BlockId::Number(block_number))
+        .await?
+    {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok(Some(block.block_hash)),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => Ok(None),
+    }
+}
+
+///
+/// Waits until all nodes agree on the hash of the lowest of their tips, retrying within
+/// `options.budget`. Fails if the nodes keep disagreeing, ex: if one of them is on a fork.
+///
+pub async fn align_tips<P: Provider>(
+    clients: &HashMap<String, P>,
+    options: &TipOptions,
+) -> anyhow::Result<AlignedTip> {
+    let mut names = clients.keys().collect::<Vec<_>>();
+    names.sort();
+    anyhow::ensure!(!names.is_empty(), "No node to align");
+
+    let deadline = Instant::now() + options.budget;
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let mut tips = vec![];
+        for name in names.iter() {
+            tips.push(clients[*name].block_number().await?);
+        }
+        let block_number = *tips.iter().min().unwrap();
+
+        let mut hashes = vec![];
+        for name in names.iter() {
+            hashes.push(block_hash(&clients[*name], block_number).await?);
+        }
+
+        if let Some(Some(block_hash)) = hashes.first().copied() {
+            if hashes.iter().all(|hash| *hash == Some(block_hash)) {
+                let lag = names
+                    .iter()
+                    .zip(tips.iter())
+                    .map(|(name, tip)| (name.to_string(), tip - block_number))
+                    .collect();
+
+                return Ok(AlignedTip {
+                    block_number,
+                    block_hash,
+                    lag,
+                    attempts,
+                });
+            }
+        }
+
+        if Instant::now() + options.poll_interval > deadline {
+            let hashes = names
+                .iter()
+                .zip(hashes.iter())
+                .map(|(name, hash)| format!("{name}: {hash:?}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            bail!(
+                "Nodes did not agree on block {block_number} within {:?} ({hashes})",
+                options.budget
+            );
+        }
+
+        log::debug!("Nodes disagree on block {block_number}, retrying");
+        sleep(options.poll_interval).await;
+    }
+}
+
+///
+/// Aligns the nodes on a common block then runs `query` on each node, pinned to that block
+/// by hash. Nodes being a few blocks apart is reported as tip lag in the [AlignedTip]
+/// rather than as a mismatch.
+///
+pub async fn pinned<'a, P, F, Fut, T>(
+    clients: &'a HashMap<String, P>,
+    options: &TipOptions,
+    query: F,
+) -> anyhow::Result<(AlignedTip, HashMap<String, T>)>
+where
+    P: Provider,
+    F: Fn(&'a P, BlockId) -> Fut,
+    Fut: Future<Output = Result<T, ProviderError>> + 'a,
+{
+    let tip = align_tips(clients, options).await?;
+    log::info!("{tip}");
+
+    let block_id = BlockId::Hash(tip.block_hash);
+    let mut responses = HashMap::new();
+    for (name, client) in clients.iter() {
+        let response = query(client, block_id)
+            .await
+            .map_err(|e| anyhow!("{name}: {e}"))?;
+        responses.insert(name.clone(), response);
+    }
+
+    Ok((tip, responses))
+}
+
+///
+/// Runs `query` on each node with the `latest` tag, once all nodes are on the same tip and
+/// stay on it until every node has answered, retrying within `options.budget`. The
+/// [AlignedTip] returned is the block `latest` referred to, so that responses can be
+/// checked against the same query pinned to it.
+///
+/// Nodes which do not stay on the same tip, ex: because one of them lags behind, are
+/// queried [pinned] to their common block instead, and the lag is logged rather than
+/// failing.
+///
+pub async fn latest<'a, P, F, Fut, T>(
+    clients: &'a HashMap<String, P>,
+    options: &TipOptions,
+    query: F,
+) -> anyhow::Result<(AlignedTip, HashMap<String, T>)>
+where
+    P: Provider,
+    F: Fn(&'a P, BlockId) -> Fut,
+    Fut: Future<Output = Result<T, ProviderError>> + 'a,
+{
+    let deadline = Instant::now() + options.budget;
+
+    loop {
+        let remaining = TipOptions {
+            budget: deadline.saturating_duration_since(Instant::now()),
+            ..options.clone()
+        };
+        let tip = align_tips(clients, &remaining).await?;
+
+        if tip.lag.iter().all(|(_, lag)| *lag == 0) {
+            let mut responses = HashMap::new();
+            for (name, client) in clients.iter() {
+                let response = query(client, BlockId::Tag(BlockTag::Latest))
+                    .await
+                    .map_err(|e| anyhow!("{name}: {e}"))?;
+                responses.insert(name.clone(), response);
+            }
+
+            let mut moved = false;
+            for client in clients.values() {
+                moved |= client.block_number().await? != tip.block_number;
+            }
+            if !moved {
+                log::info!("{tip}");
+                return Ok((tip, responses));
+            }
+        }
+
+        if Instant::now() + options.poll_interval > deadline {
+            log::warn!(
+                "Nodes did not stay on the same tip within {:?}, querying their common block",
+                options.budget
+            );
+            return pinned(clients, options, query).await;
+        }
+
+        log::debug!("Nodes are not on the same tip, retrying");
+        sleep(options.poll_interval).await;
+    }
+}
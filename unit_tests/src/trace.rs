@@ -0,0 +1,317 @@
+use std::fmt;
+
+use serde_json::{Number, Value};
+use starknet_core::types::TransactionTraceWithHash;
+
+use crate::diff::{sort_arrays, Difference};
+
+/// Invocations of a transaction trace, in execution order
+const INVOCATIONS: [&str; 5] = [
+    "validate_invocation",
+    "constructor_invocation",
+    "execute_invocation",
+    "function_invocation",
+    "fee_transfer_invocation",
+];
+
+///
+/// How a field of a trace, and everything nested in it, is compared.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    Exact,
+    Ignore,
+    /// Arrays are compared as sets, ex: state diffs
+    Unordered,
+    /// Numbers may differ by this fraction of the largest one, ex: execution resources
+    Relative(f64),
+}
+
+///
+/// First difference between two transaction traces, located by its call path, ex:
+/// `execute_invocation > calls[2] > calls[0]`, and its pointer inside that call.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceDifference {
+    /// `None` when comparing a single trace
+    pub transaction_hash: Option<String>,
+    pub call_path: Vec<String>,
+    pub difference: Difference,
+}
+
+impl fmt::Display for TraceDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(transaction_hash) = &self.transaction_hash {
+            write!(f, "{transaction_hash}: ")?;
+        }
+        if !self.call_path.is_empty() {
+            write!(f, "{}: ", self.call_path.join(" > "))?;
+        }
+        write!(f, "{}", self.difference)
+    }
+}
+
+///
+/// Compares transaction traces call by call, with a [Tolerance] per field name. The rule
+/// of a field applies to everything nested in it, unless overridden by a nested field.
+///
+#[derive(Debug, Clone)]
+pub struct TraceComparator {
+    rules: Vec<(String, Tolerance)>,
+}
+
+impl Default for TraceComparator {
+    /// Exact comparison, except for state diffs which nodes are free to order differently
+    fn default() -> Self {
+        Self::new().with_rule("state_diff", Tolerance::Unordered)
+    }
+}
+
+impl TraceComparator {
+    /// Exact comparison of every field
+    pub fn new() -> Self {
+        Self { rules: vec![] }
+    }
+
+    pub fn with_rule(mut self, field: &str, tolerance: Tolerance) -> Self {
+        self.rules.retain(|(name, _)| name != field);
+        self.rules.push((field.to_string(), tolerance));
+        self
+    }
+
+    fn rule(&self, field: &str) -> Option<Tolerance> {
+        self.rules
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, tolerance)| *tolerance)
+    }
+
+    ///
+    /// Compares the traces of a block transaction by transaction, and returns the first
+    /// difference.
+    ///
+    pub fn compare_block(
+        &self,
+        left: &[TransactionTraceWithHash],
+        right: &[TransactionTraceWithHash],
+    ) -> anyhow::Result<Option<TraceDifference>> {
+        if left.len() != right.len() {
+            return Ok(Some(TraceDifference {
+                transaction_hash: None,
+                call_path: vec![],
+                difference: Difference {
+                    pointer: String::from("/length"),
+                    left: left.len().into(),
+                    right: right.len().into(),
+                },
+            }));
+        }
+
+        for (left, right) in left.iter().zip(right.iter()) {
+            let left = serde_json::to_value(left)?;
+            let right = serde_json::to_value(right)?;
+            let transaction_hash = left["transaction_hash"].as_str().map(String::from);
+
+            if left["transaction_hash"] != right["transaction_hash"] {
+                return Ok(Some(TraceDifference {
+                    transaction_hash,
+                    call_path: vec![],
+                    difference: Difference {
+                        pointer: String::from("/transaction_hash"),
+                        left: left["transaction_hash"].clone(),
+                        right: right["transaction_hash"].clone(),
+                    },
+                }));
+            }
+
+            if let Some(difference) = self.compare_trace(&left["trace_root"], &right["trace_root"])
+            {
+                return Ok(Some(TraceDifference {
+                    transaction_hash,
+                    ..difference
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    ///
+    /// Compares two transaction traces, as json, invocation by invocation in execution
+    /// order, then their remaining fields such as the state diff.
+    ///
+    pub fn compare_trace(&self, left: &Value, right: &Value) -> Option<TraceDifference> {
+        for invocation in INVOCATIONS {
+            let path = vec![invocation.to_string()];
+            let difference = self.compare_invocation(
+                path,
+                left.get(invocation).unwrap_or(&Value::Null),
+                right.get(invocation).unwrap_or(&Value::Null),
+            );
+            if difference.is_some() {
+                return difference;
+            }
+        }
+
+        let others = |value: &Value| match value {
+            Value::Object(object) => {
+                let mut object = object.clone();
+                object.retain(|key, _| !INVOCATIONS.contains(&key.as_str()));
+                Value::Object(object)
+            }
+            value => value.clone(),
+        };
+
+        self.compare_field(
+            String::new(),
+            &others(left),
+            &others(right),
+            Tolerance::Exact,
+        )
+        .map(|difference| TraceDifference {
+            transaction_hash: None,
+            call_path: vec![],
+            difference,
+        })
+    }
+
+    /// Fields of the call first, then its nested calls depth first
+    fn compare_invocation(
+        &self,
+        call_path: Vec<String>,
+        left: &Value,
+        right: &Value,
+    ) -> Option<TraceDifference> {
+        let at = |call_path: Vec<String>, difference: Difference| TraceDifference {
+            transaction_hash: None,
+            call_path,
+            difference,
+        };
+
+        let (Value::Object(l), Value::Object(r)) = (left, right) else {
+            return self
+                .compare_field(String::new(), left, right, Tolerance::Exact)
+                .map(|difference| at(call_path, difference));
+        };
+
+        let keys = l.keys().chain(r.keys().filter(|key| !l.contains_key(*key)));
+        for key in keys.filter(|key| *key != "calls") {
+            let tolerance = self.rule(key).unwrap_or(Tolerance::Exact);
+            if let Some(difference) = self.compare_field(
+                format!("/{key}"),
+                l.get(key).unwrap_or(&Value::Null),
+                r.get(key).unwrap_or(&Value::Null),
+                tolerance,
+            ) {
+                return Some(at(call_path, difference));
+            }
+        }
+
+        let no_calls = vec![];
+        let left_calls = l
+            .get("calls")
+            .and_then(Value::as_array)
+            .unwrap_or(&no_calls);
+        let right_calls = r
+            .get("calls")
+            .and_then(Value::as_array)
+            .unwrap_or(&no_calls);
+        if left_calls.len() != right_calls.len() {
+            return Some(at(
+                call_path,
+                Difference {
+                    pointer: String::from("/calls/length"),
+                    left: left_calls.len().into(),
+                    right: right_calls.len().into(),
+                },
+            ));
+        }
+
+        for (i, (left, right)) in left_calls.iter().zip(right_calls.iter()).enumerate() {
+            let mut path = call_path.clone();
+            path.push(format!("calls[{i}]"));
+
+            let difference = self.compare_invocation(path, left, right);
+            if difference.is_some() {
+                return difference;
+            }
+        }
+
+        None
+    }
+
+    fn compare_field(
+        &self,
+        pointer: String,
+        left: &Value,
+        right: &Value,
+        tolerance: Tolerance,
+    ) -> Option<Difference> {
+        if left == right || tolerance == Tolerance::Ignore {
+            return None;
+        }
+
+        match (left, right) {
+            (Value::Object(l), Value::Object(r)) => {
+                let keys = l.keys().chain(r.keys().filter(|key| !l.contains_key(*key)));
+                for key in keys {
+                    let difference = self.compare_field(
+                        format!("{pointer}/{key}"),
+                        l.get(key).unwrap_or(&Value::Null),
+                        r.get(key).unwrap_or(&Value::Null),
+                        self.rule(key).unwrap_or(tolerance),
+                    );
+                    if difference.is_some() {
+                        return difference;
+                    }
+                }
+                None
+            }
+            (Value::Array(_), Value::Array(_)) if tolerance == Tolerance::Unordered => {
+                let (mut l, mut r) = (left.clone(), right.clone());
+                sort_arrays(&mut l);
+                sort_arrays(&mut r);
+
+                if l == r {
+                    None
+                } else {
+                    self.compare_field(pointer, &l, &r, Tolerance::Exact)
+                }
+            }
+            (Value::Array(l), Value::Array(r)) => {
+                for i in 0..l.len().max(r.len()) {
+                    let difference = self.compare_field(
+                        format!("{pointer}/{i}"),
+                        l.get(i).unwrap_or(&Value::Null),
+                        r.get(i).unwrap_or(&Value::Null),
+                        tolerance,
+                    );
+                    if difference.is_some() {
+                        return difference;
+                    }
+                }
+                None
+            }
+            (Value::Number(l), Value::Number(r)) => match tolerance {
+                Tolerance::Relative(fraction) if within(l, r, fraction) => None,
+                _ => Some(Difference {
+                    pointer,
+                    left: left.clone(),
+                    right: right.clone(),
+                }),
+            },
+            _ => Some(Difference {
+                pointer,
+                left: left.clone(),
+                right: right.clone(),
+            }),
+        }
+    }
+}
+
+fn within(left: &Number, right: &Number, fraction: f64) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(l), Some(r)) => (l - r).abs() <= fraction * l.abs().max(r.abs()),
+        _ => false,
+    }
+}
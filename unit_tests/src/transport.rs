@@ -0,0 +1,183 @@
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use starknet_providers::jsonrpc::{
+    HttpTransport, HttpTransportError, JsonRpcError, JsonRpcMethod, JsonRpcResponse,
+    JsonRpcTransport,
+};
+use url::Url;
+
+use crate::cassette::Cassette;
+
+///
+/// Raw JSON-RPC response, as sent by the node and before it is deserialized into
+/// starknet-rs types.
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RawResponse {
+    Success { result: Value },
+    Error { error: RawError },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+///
+/// Hook called by [DittoTransport] on every raw response. Returning an error fails the
+/// request, which in turn fails the test which issued it.
+///
+pub trait RpcObserver: Send + Sync {
+    fn observe(&self, method: &str, params: &Value, response: &RawResponse) -> anyhow::Result<()>;
+}
+
+///
+/// [HttpTransport] wrapper giving access to raw requests and responses through
+/// [RpcObserver]s, or replaying the responses of a [Cassette]. This is the transport used
+/// by all test fixtures.
+///
+pub struct DittoTransport {
+    inner: Backend,
+    observers: Vec<Arc<dyn RpcObserver>>,
+}
+
+enum Backend {
+    Http(HttpTransport),
+    Cassette(Cassette),
+}
+
+impl DittoTransport {
+    pub fn new(url: Url) -> Self {
+        Self {
+            inner: Backend::Http(HttpTransport::new(url)),
+            observers: vec![],
+        }
+    }
+
+    /// Transport answering with the responses recorded in `cassette`, without a node
+    pub fn replay(cassette: Cassette) -> Self {
+        Self {
+            inner: Backend::Cassette(cassette),
+            observers: vec![],
+        }
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn RpcObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum DittoTransportError {
+    Http(HttpTransportError),
+    Json(serde_json::Error),
+    Observer(anyhow::Error),
+    Cassette(String),
+}
+
+impl fmt::Display for DittoTransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{e}"),
+            Self::Json(e) => write!(f, "{e}"),
+            Self::Observer(e) => write!(f, "{e:#}"),
+            Self::Cassette(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for DittoTransportError {}
+
+impl From<serde_json::Error> for DittoTransportError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+///
+/// Name of a JSON-RPC method as sent over the wire, ex: `starknet_getBlockWithTxs`.
+///
+pub fn method_name(method: &JsonRpcMethod) -> String {
+    match serde_json::to_value(method) {
+        Ok(Value::String(name)) => name,
+        _ => format!("{method:?}"),
+    }
+}
+
+#[async_trait]
+impl JsonRpcTransport for DittoTransport {
+    type Error = DittoTransportError;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send,
+        R: DeserializeOwned,
+    {
+        let name = method_name(&method);
+        let params = serde_json::to_value(params)?;
+        let (id, response) = match &self.inner {
+            Backend::Http(http) => {
+                match http
+                    .send_request::<Value, Value>(method, params.clone())
+                    .await
+                    .map_err(DittoTransportError::Http)?
+                {
+                    JsonRpcResponse::Success { id, result } => {
+                        (id, RawResponse::Success { result })
+                    }
+                    JsonRpcResponse::Error { id, error } => (
+                        id,
+                        RawResponse::Error {
+                            error: RawError {
+                                code: error.code,
+                                message: error.message,
+                                data: error.data,
+                            },
+                        },
+                    ),
+                }
+            }
+            Backend::Cassette(cassette) => {
+                let response = cassette.response(&name, &params).ok_or_else(|| {
+                    DittoTransportError::Cassette(format!(
+                        "No recorded response to {name} with params {params}"
+                    ))
+                })?;
+                (1, response)
+            }
+        };
+
+        for observer in self.observers.iter() {
+            observer
+                .observe(&name, &params, &response)
+                .map_err(DittoTransportError::Observer)?;
+        }
+
+        match response {
+            RawResponse::Success { result } => Ok(JsonRpcResponse::Success {
+                id,
+                result: serde_json::from_value(result)?,
+            }),
+            RawResponse::Error { error } => Ok(JsonRpcResponse::Error {
+                id,
+                error: JsonRpcError {
+                    code: error.code,
+                    message: error.message,
+                    data: error.data,
+                },
+            }),
+        }
+    }
+}
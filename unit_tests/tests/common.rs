@@ -8,3 +8,5 @@ pub use rstest::*;
 pub use unit_tests::constants::*;
 #[allow(unused_imports)]
 pub use unit_tests::fixtures::*;
+#[allow(unused_imports)]
+pub use unit_tests::transport::DittoTransport;
@@ -5,10 +5,7 @@ use common::*;
 use starknet_core::types::{
     BroadcastedInvokeTransaction, FieldElement, StarknetError, TransactionStatus,
 };
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
-};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
 use std::assert_matches::assert_matches;
 use std::thread;
 use std::time::Duration;
@@ -44,7 +41,7 @@ use std::time::Duration;
 /// Following tests runs using V1 Invoke Transaction (params follow starknet-rs implementation)
 #[rstest]
 #[tokio::test]
-async fn fail_if_param_(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_if_param_(deoxys: JsonRpcClient<DittoTransport>) {
     let invalid_invoke_transaction = BroadcastedInvokeTransaction {
         sender_address: FieldElement::from_hex_be("valid_address").unwrap(),
         calldata: vec![FieldElement::from_hex_be("calldata_array").unwrap()],
@@ -68,7 +65,7 @@ async fn fail_if_param_(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn fail_if_insufficient_max_fee(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_if_insufficient_max_fee(deoxys: JsonRpcClient<DittoTransport>) {
     let invalid_invoke_transaction = BroadcastedInvokeTransaction {
         sender_address: FieldElement::from_hex_be("valid_address").unwrap(),
         calldata: vec![FieldElement::from_hex_be("calldata_array").unwrap()],
@@ -92,7 +89,7 @@ async fn fail_if_insufficient_max_fee(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn fail_if_bad_calldata(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_if_bad_calldata(deoxys: JsonRpcClient<DittoTransport>) {
     let invalid_invoke_transaction = BroadcastedInvokeTransaction {
         sender_address: FieldElement::from_hex_be("valid_address").unwrap(),
         calldata: vec![FieldElement::from_hex_be("0x000000").unwrap()], //here calldata is invalid
@@ -116,7 +113,7 @@ async fn fail_if_bad_calldata(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn works_ok_with_valid_params(deoxys: JsonRpcClient<HttpTransport>) {
+async fn works_ok_with_valid_params(deoxys: JsonRpcClient<DittoTransport>) {
     let valid_invoke_transaction = BroadcastedInvokeTransaction {
         sender_address: FieldElement::from_hex_be("valid_address").unwrap(),
         calldata: vec![FieldElement::from_hex_be("calldata_array").unwrap()],
@@ -0,0 +1,104 @@
+mod common;
+use common::*;
+
+use std::time::Duration;
+
+use unit_tests::bench::{
+    baseline::{compare, mann_whitney, RegressionOptions},
+    latency::LatencyReport,
+    Method, Stats,
+};
+
+/// Latencies from `from` to `from + 100` ms, over 100 seconds
+fn stats(from: u64) -> Stats {
+    let mut stats = Stats::new();
+    for ms in from..from + 100 {
+        stats.record(Duration::from_millis(ms));
+    }
+    stats.elapsed = Duration::from_secs(100);
+    stats
+}
+
+fn report(stats: Stats) -> LatencyReport {
+    let mut report = LatencyReport::default();
+    report
+        .results
+        .entry(Method::GetStorageAt)
+        .or_default()
+        .insert(String::from(DEOXYS), stats);
+    report
+}
+
+///
+/// Benchmark baselines
+///
+/// purpose: histograms are saved to and loaded from json without losing precision.
+/// success case: percentiles, errors and elapsed time are the same after a round trip.
+///
+#[rstest]
+fn work_stats_json() {
+    let mut before = stats(1);
+    before.record_error();
+
+    let json = serde_json::to_string(&before).unwrap();
+    let after = serde_json::from_str::<Stats>(&json).unwrap();
+
+    assert_eq!(after.count(), before.count());
+    assert_eq!(after.errors, before.errors);
+    assert_eq!(after.elapsed, before.elapsed);
+    for quantile in [0.5, 0.9, 0.99, 1.0] {
+        assert_eq!(after.quantile(quantile), before.quantile(quantile));
+    }
+}
+
+///
+/// Regression detection
+///
+/// purpose: flag latency and throughput regressions which are significant and above the
+///          threshold.
+/// success case: identical runs do not regress, a run twice as slow does, a lower
+///               throughput with the same latencies does not.
+///
+#[rstest]
+fn work_compare() {
+    let baseline = stats(100);
+    let slower = stats(200);
+
+    assert!(mann_whitney(&baseline.histogram, &baseline.histogram).abs() < 0.01);
+    assert!(mann_whitney(&baseline.histogram, &slower.histogram) > 10.0);
+    assert!(mann_whitney(&slower.histogram, &baseline.histogram) < -10.0);
+
+    let options = RegressionOptions::default();
+    assert!(compare(
+        &report(baseline.clone()),
+        &report(baseline.clone()),
+        &options
+    )
+    .is_empty());
+    assert!(compare(&report(slower.clone()), &report(baseline.clone()), &options).is_empty());
+
+    let regressions = compare(&report(baseline), &report(slower), &options);
+    let metrics = regressions
+        .iter()
+        .map(|regression| regression.metric)
+        .collect::<Vec<_>>();
+    assert_eq!(metrics, vec!["p50 (ms)", "p99 (ms)"]);
+
+    // throughput drops only count if requests got slower
+    let stalled = Stats {
+        elapsed: Duration::from_secs(200),
+        ..stats(100)
+    };
+    assert!(compare(&report(stats(100)), &report(stalled), &options).is_empty());
+
+    let slower = Stats {
+        elapsed: Duration::from_secs(200),
+        ..stats(200)
+    };
+    let regressions = compare(&report(stats(100)), &report(slower), &options);
+    let throughput = regressions
+        .iter()
+        .find(|regression| regression.metric == "throughput (req/s)")
+        .expect("Throughput regressed");
+    assert!(throughput.z.is_some());
+}
@@ -0,0 +1,254 @@
+mod common;
+use common::*;
+
+use serde_json::{json, Value};
+use starknet_core::types::FieldElement;
+use unit_tests::{
+    batch::{match_responses, BatchClient, Request},
+    bench::{batch::request as bench_request, Method, Targets},
+    equivalence::Probe,
+    transport::RawResponse,
+};
+
+/// JSON-RPC error code of an invalid request, such as an empty batch
+const INVALID_REQUEST: i64 = -32600;
+/// Starknet error code of a block which does not exist
+const BLOCK_NOT_FOUND: i64 = 24;
+
+fn get_block(block_number: u64) -> Request {
+    Request::new(
+        "starknet_getBlockWithTxHashes",
+        json!({ "block_id": { "block_number": block_number } }),
+    )
+}
+
+fn block_number(response: &RawResponse) -> Option<u64> {
+    match response {
+        RawResponse::Success { result } => result.get("block_number")?.as_u64(),
+        RawResponse::Error { .. } => None,
+    }
+}
+
+///
+/// JSON-RPC batches
+///
+/// purpose: match the responses of a batch to its requests by id.
+/// success case: responses are reordered by id, missing, duplicate or unknown ids and
+///               rejected batches are errors.
+///
+#[rstest]
+fn work_match_responses() {
+    let response = |id: u64| json!({ "jsonrpc": "2.0", "id": id, "result": id });
+
+    let responses = match_responses(3, json!([response(2), response(0), response(1)]))
+        .expect("Responses should match requests");
+    assert_eq!(
+        responses,
+        (0..3)
+            .map(|id| RawResponse::Success { result: json!(id) })
+            .collect::<Vec<_>>()
+    );
+
+    assert!(match_responses(3, json!([response(0), response(1)])).is_err());
+    assert!(match_responses(2, json!([response(0), response(0)])).is_err());
+    assert!(match_responses(2, json!([response(0), response(2)])).is_err());
+
+    let rejected = json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": INVALID_REQUEST, "message": "Invalid request" },
+    });
+    assert!(match_responses(1, rejected).is_err());
+}
+
+///
+/// JSON-RPC batches
+///
+/// purpose: build the raw requests of the batch benchmark.
+/// success case: params are named as in the spec and cycle through the targets, receipts
+///               are not supported without transactions.
+///
+#[rstest]
+fn work_bench_request() {
+    let targets = Targets {
+        blocks: vec![1, 2],
+        transactions: vec![],
+        probe: Probe::default(),
+    };
+
+    let request = bench_request(Method::GetStorageAt, &targets, 1);
+    assert_eq!(request.method, "starknet_getStorageAt");
+    assert_eq!(request.params["block_id"], json!({ "block_number": 2 }));
+    assert_eq!(
+        request.params["contract_address"],
+        json!("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7")
+    );
+
+    let request = bench_request(Method::GetStateUpdate, &targets, 2);
+    assert_eq!(request.params, json!({ "block_id": { "block_number": 1 } }));
+
+    // no transaction to get the receipt of
+    assert!(!targets.supports(Method::GetTransactionReceipt));
+    let targets = Targets {
+        transactions: vec![FieldElement::from(10u64)],
+        ..targets
+    };
+    let request = bench_request(Method::GetTransactionReceipt, &targets, 3);
+    assert_eq!(request.params, json!({ "transaction_hash": "0xa" }));
+}
+
+///
+/// JSON-RPC batches
+///
+/// purpose: send a batch of block requests.
+/// success case: every request gets the response it would get on its own, whatever the
+///               order responses are sent in.
+///
+#[require(block_min = 10, spec_version = "0.5.1", live = true)]
+#[rstest]
+#[tokio::test]
+async fn work_batch_ordering(batch_deoxys: BatchClient, batch_pathfinder: BatchClient) {
+    let requests = (0..10).rev().map(get_block).collect::<Vec<_>>();
+
+    for (client, err) in [
+        (batch_deoxys, ERR_DEOXYS),
+        (batch_pathfinder, ERR_PATHFINDER),
+    ] {
+        let responses = client.batch(&requests).await.expect(err);
+
+        assert_eq!(
+            responses.iter().map(block_number).collect::<Vec<_>>(),
+            (0..10).rev().map(Some).collect::<Vec<_>>()
+        );
+        assert_eq!(responses[0], client.call(&requests[0]).await.expect(err));
+    }
+}
+
+///
+/// JSON-RPC batches
+///
+/// purpose: send a batch mixing valid requests and a request which fails.
+/// success case: the failing request gets an error, the others still succeed.
+///
+#[require(block_min = 1, spec_version = "0.5.1", live = true)]
+#[rstest]
+#[tokio::test]
+async fn work_batch_mixed_errors(batch_deoxys: BatchClient, batch_pathfinder: BatchClient) {
+    let requests = vec![get_block(0), get_block(u64::MAX), get_block(1)];
+
+    for (client, err) in [
+        (batch_deoxys, ERR_DEOXYS),
+        (batch_pathfinder, ERR_PATHFINDER),
+    ] {
+        let responses = client.batch(&requests).await.expect(err);
+
+        assert_eq!(block_number(&responses[0]), Some(0));
+        assert!(matches!(
+            &responses[1],
+            RawResponse::Error { error } if error.code == BLOCK_NOT_FOUND
+        ));
+        assert_eq!(block_number(&responses[2]), Some(1));
+    }
+}
+
+///
+/// JSON-RPC batches
+///
+/// purpose: send an empty batch.
+/// fail case: the node answers with a single invalid request error, as per the JSON-RPC
+///            2.0 spec.
+///
+#[require(spec_version = "0.5.1", live = true)]
+#[rstest]
+#[tokio::test]
+async fn fail_empty_batch(batch_deoxys: BatchClient, batch_pathfinder: BatchClient) {
+    for (client, err) in [
+        (batch_deoxys, ERR_DEOXYS),
+        (batch_pathfinder, ERR_PATHFINDER),
+    ] {
+        let response = client.post(&json!([])).await.expect(err);
+
+        assert_eq!(
+            response.pointer("/error/code").and_then(Value::as_i64),
+            Some(INVALID_REQUEST),
+            "Unexpected response to an empty batch: {response}"
+        );
+    }
+}
+
+///
+/// JSON-RPC batches
+///
+/// purpose: send a batch whose requests share the same id.
+/// success case: every request is still answered, with its id.
+///
+#[require(block_min = 1, spec_version = "0.5.1", live = true)]
+#[rstest]
+#[tokio::test]
+async fn work_batch_duplicate_ids(batch_deoxys: BatchClient, batch_pathfinder: BatchClient) {
+    let body = json!([get_block(0).to_json(1), get_block(1).to_json(1)]);
+
+    for (client, err) in [
+        (batch_deoxys, ERR_DEOXYS),
+        (batch_pathfinder, ERR_PATHFINDER),
+    ] {
+        let response = client.post(&body).await.expect(err);
+        let entries = response
+            .as_array()
+            .unwrap_or_else(|| panic!("Expected an array of responses, got {response}"));
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry["id"] == json!(1)));
+
+        let mut blocks = entries
+            .iter()
+            .filter_map(|entry| entry.pointer("/result/block_number")?.as_u64())
+            .collect::<Vec<_>>();
+        blocks.sort();
+        assert_eq!(blocks, vec![0, 1]);
+    }
+}
+
+///
+/// JSON-RPC batches
+///
+/// purpose: send batches of increasing size.
+/// success case: each batch is either answered in full or rejected as a whole, by a
+///               JSON-RPC error or an HTTP 4xx status, never truncated.
+///
+#[require(spec_version = "0.5.1", live = true)]
+#[rstest]
+#[tokio::test]
+async fn work_batch_size_limit(batch_deoxys: BatchClient, batch_pathfinder: BatchClient) {
+    let request = Request::new("starknet_blockNumber", json!([]));
+
+    for client in [batch_deoxys, batch_pathfinder] {
+        for size in [1, 10, 100, 1000, 10_000] {
+            let body = Value::Array((0..size).map(|id| request.to_json(id)).collect());
+
+            // transport errors, such as timeouts, are not a rejection of the batch
+            let (status, text) = client
+                .send(&body)
+                .await
+                .expect("Error while sending the batch");
+
+            if status.is_client_error() {
+                log::info!("Batch of {size} rejected: {status} {text}");
+                continue;
+            }
+
+            let response: Value = serde_json::from_str(&text)
+                .unwrap_or_else(|_| panic!("Expected a json response, got {status}: {text}"));
+            match response {
+                Value::Array(entries) => assert_eq!(entries.len(), size as usize),
+                response => {
+                    assert!(
+                        response.get("error").is_some(),
+                        "Unexpected response to a batch of {size}: {response}"
+                    );
+                    log::info!("Batch of {size} rejected: {}", response["error"]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+mod common;
+use common::*;
+
+use std::time::Duration;
+
+use starknet_providers::JsonRpcClient;
+use unit_tests::{
+    bench::{
+        latency::{run_latency, LatencyWorkload},
+        Method, Stats, Targets,
+    },
+    equivalence::Probe,
+};
+
+///
+/// Benchmark statistics
+///
+/// purpose: latency percentiles and error rates are computed from the recorded requests.
+/// success case: percentiles of successful requests are within the histogram's precision,
+///               merged stats add up.
+///
+#[rstest]
+fn work_stats() {
+    let mut stats = Stats::new();
+    for ms in 1..=100 {
+        stats.record(Duration::from_millis(ms));
+    }
+    stats.record_error();
+    stats.elapsed = Duration::from_secs(101);
+
+    // failures are counted but not timed
+    assert_eq!(stats.count(), 101);
+    assert_eq!(stats.histogram.len(), 100);
+    assert!(stats.quantile(0.5).abs_diff(Duration::from_millis(50)) < Duration::from_millis(1));
+    assert!(stats.max().abs_diff(Duration::from_millis(100)) < Duration::from_millis(1));
+    assert!((stats.error_rate() - 1.0 / 101.0).abs() < f64::EPSILON);
+    assert!((stats.throughput() - 1.0).abs() < f64::EPSILON);
+
+    let mut merged = Stats::new();
+    merged.merge(&stats);
+    merged.merge(&stats);
+    assert_eq!(merged.count(), 202);
+    assert_eq!(merged.errors, 2);
+}
+
+///
+/// Benchmark methods
+///
+/// purpose: methods are selected by their snake_case or RPC name.
+/// success case: both names parse to the same method.
+///
+#[rstest]
+fn work_method_names() {
+    for method in Method::ALL {
+        assert_eq!(method.to_string().parse::<Method>().unwrap(), method);
+        assert_eq!(method.name().parse::<Method>().unwrap(), method);
+    }
+    assert!("get_storage".parse::<Method>().is_err());
+}
+
+///
+/// Latency benchmark
+///
+/// purpose: every method of the workload is measured on every node.
+/// success case: the report holds as many requests as the workload, per method and node.
+///
+#[require(block_min = 50_000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_latency(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let targets = Targets::fetch(&pathfinder, vec![BLOCK_LEGACY, 50_000])
+        .await
+        .expect(ERR_PATHFINDER);
+    let workload = LatencyWorkload {
+        methods: vec![Method::GetStorageAt, Method::GetTransactionReceipt],
+        requests: 10,
+        concurrency: 2,
+        warmup: 1,
+    };
+
+    let report = run_latency(
+        &[(DEOXYS, &deoxys), (PATHFINDER, &pathfinder)],
+        &targets,
+        &workload,
+    )
+    .await;
+    println!("{report}");
+
+    for method in workload.methods {
+        for node in [DEOXYS, PATHFINDER] {
+            let stats = report.get(method, node).unwrap();
+            assert_eq!(stats.count(), 10);
+            assert_eq!(stats.errors, 0);
+        }
+    }
+}
+
+///
+/// Benchmark targets
+///
+/// purpose: benchmark methods which need a transaction on blocks without any.
+/// success case: receipts are skipped and reported as such, rather than measured on
+///               another method.
+///
+#[rstest]
+#[tokio::test]
+async fn work_skip_unsupported() {
+    let targets = Targets {
+        blocks: vec![1],
+        transactions: vec![],
+        probe: Probe::default(),
+    };
+    let workload = LatencyWorkload {
+        methods: vec![Method::GetTransactionReceipt],
+        ..Default::default()
+    };
+
+    let report = run_latency::<JsonRpcClient<DittoTransport>>(&[], &targets, &workload).await;
+    assert!(report.results.is_empty());
+    assert_eq!(report.skipped, vec![Method::GetTransactionReceipt]);
+    assert!(report.to_string().contains("skipped"));
+}
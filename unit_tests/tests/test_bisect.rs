@@ -0,0 +1,171 @@
+mod common;
+use common::*;
+
+use serde_json::{json, Value};
+use starknet_providers::JsonRpcClient;
+use unit_tests::{
+    batch::Request,
+    bisect::{bisect, compare_components, BisectComponent},
+    cassette::Cassette,
+    replay::CapturedCall,
+    transport::RawResponse,
+};
+
+/// Blocks of the fake chains, bisected in at most 10 steps after checking both ends
+const CHAIN_LEN: u64 = 1024;
+
+fn call(method: &str, block_number: u64, result: Value) -> CapturedCall {
+    CapturedCall {
+        timestamp_ms: 0,
+        request: Request::new(
+            method,
+            json!({ "block_id": { "block_number": block_number } }),
+        ),
+        response: Some(RawResponse::Success { result }),
+    }
+}
+
+///
+/// Node with [CHAIN_LEN] blocks, whose block hashes and state roots are offset by `fork`
+/// from block `diverges_from` on. Blocks are empty.
+///
+fn node(diverges_from: u64, fork: u64) -> JsonRpcClient<DittoTransport> {
+    let hash = |block_number: u64| {
+        let offset = if block_number >= diverges_from {
+            fork
+        } else {
+            0
+        };
+        format!("{:#x}", block_number + offset + 1)
+    };
+
+    let calls = (0..CHAIN_LEN).flat_map(|block_number| {
+        let block = json!({
+            "status": "ACCEPTED_ON_L2",
+            "block_hash": hash(block_number),
+            "parent_hash": hash(block_number.saturating_sub(1)),
+            "block_number": block_number,
+            "new_root": hash(block_number),
+            "timestamp": 1700000000,
+            "sequencer_address": "0x1",
+            "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+            "starknet_version": "0.12.3",
+            "transactions": []
+        });
+        let state_update = json!({
+            "block_hash": hash(block_number),
+            "new_root": hash(block_number),
+            "old_root": hash(block_number.saturating_sub(1)),
+            "state_diff": {
+                "storage_diffs": [],
+                "deprecated_declared_classes": [],
+                "declared_classes": [],
+                "deployed_contracts": [],
+                "replaced_classes": [],
+                "nonces": []
+            }
+        });
+
+        [
+            call("starknet_getBlockWithTxHashes", block_number, block),
+            call("starknet_getStateUpdate", block_number, state_update),
+        ]
+    });
+
+    JsonRpcClient::new(DittoTransport::replay(Cassette::new(calls.collect())))
+}
+
+///
+/// Bisection of the first divergent block
+///
+/// purpose: a node never diverges from itself.
+/// success case: all components of a block are equal when compared with the same node.
+///
+#[require(block_min = 50_000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_same_node(deoxys: JsonRpcClient<DittoTransport>) {
+    let components = compare_components(&deoxys, &deoxys, 50_000)
+        .await
+        .expect(ERR_DEOXYS);
+
+    assert!(components.is_empty());
+}
+
+///
+/// Bisection of the first divergent block
+///
+/// purpose: find the first block whose block hash or state root differs between nodes.
+/// success case: nodes agree on the first 1000 blocks.
+///
+#[require(block_min = 1000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_without_divergence(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let divergence = bisect(&deoxys, &pathfinder, 0..=1000)
+        .await
+        .expect("Error while bisecting blocks");
+
+    if let Some(divergence) = &divergence {
+        println!("{divergence}");
+    }
+    assert!(divergence.is_none());
+}
+
+///
+/// Bisection of the first divergent block
+///
+/// purpose: find the block from which a node diverges, anywhere in the range.
+/// success case: the first divergent block is found in at most log2(range) steps, after
+///               checking both ends of the range, with its block hash and state root
+///               reported as divergent.
+///
+#[rstest]
+#[case::first_block(0)]
+#[case::second_block(1)]
+#[case::middle(377)]
+#[case::last_block(CHAIN_LEN - 1)]
+#[tokio::test]
+async fn work_with_divergence(#[case] diverges_from: u64) {
+    let deoxys = node(diverges_from, CHAIN_LEN);
+    let pathfinder = node(CHAIN_LEN, 0);
+
+    let divergence = bisect(&deoxys, &pathfinder, 0..=CHAIN_LEN - 1)
+        .await
+        .expect("Error while bisecting blocks")
+        .expect("Nodes diverge on the last block");
+    println!("{divergence}");
+
+    assert_eq!(divergence.block_number, diverges_from);
+    assert!(divergence.steps <= 2 + CHAIN_LEN.ilog2());
+
+    let components = divergence
+        .components
+        .iter()
+        .map(|(component, _)| *component)
+        .collect::<Vec<_>>();
+    assert!(components.contains(&BisectComponent::BlockHash));
+    assert!(components.contains(&BisectComponent::StateRoot));
+}
+
+///
+/// Bisection of the first divergent block
+///
+/// purpose: bisect nodes which agree on the whole range.
+/// success case: no divergence is found.
+///
+#[rstest]
+#[tokio::test]
+async fn work_without_divergence_offline() {
+    let deoxys = node(CHAIN_LEN, 0);
+    let pathfinder = node(CHAIN_LEN, 0);
+
+    let divergence = bisect(&deoxys, &pathfinder, 0..=CHAIN_LEN - 1)
+        .await
+        .expect("Error while bisecting blocks");
+
+    assert!(divergence.is_none());
+}
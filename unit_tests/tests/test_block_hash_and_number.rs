@@ -4,37 +4,45 @@ use r#macro::require;
 
 use std::collections::HashMap;
 
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider,
-};
+use starknet_core::types::{BlockHashAndNumber, BlockId, MaybePendingBlockWithTxHashes};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider};
+use unit_tests::tip::{latest, TipOptions};
 
 ///
 /// Unit test for `starknet_BlockHashAndNumber`
 ///
 /// purpose: get block hash and number on latest block.
-/// success case: retrieves correct block hash and number.
+/// success case: retrieves correct block hash and number, the same on all nodes once they
+///               are on the same tip, or on their common block if one of them lags.
 ///
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_latest_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
-    let deoxys = &clients[DEOXYS];
-    let pathfinder = &clients[PATHFINDER];
+async fn work_latest_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
+    // nodes which do not stay on the same tip are queried on their common block instead
+    let (tip, responses) = latest(
+        &clients,
+        &TipOptions::default(),
+        |client, block_id| async move {
+            match block_id {
+                BlockId::Tag(_) => client.block_hash_and_number().await,
+                block_id => match client.get_block_with_tx_hashes(block_id).await? {
+                    MaybePendingBlockWithTxHashes::Block(block) => Ok(BlockHashAndNumber {
+                        block_hash: block.block_hash,
+                        block_number: block.block_number,
+                    }),
+                    MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+                        panic!("Expected block {block_id:?}, got pending block")
+                    }
+                },
+            }
+        },
+    )
+    .await
+    .expect("Error waiting for response from nodes");
 
-    let response_deoxys = deoxys
-        .block_hash_and_number()
-        .await
-        .expect("Error waiting for response from Deoxys node");
-    let response_pathfinder = pathfinder
-        .block_hash_and_number()
-        .await
-        .expect("Error waiting for response from Deoxys node");
-    let response_expected = deoxys
-        .block_number()
-        .await
-        .expect("Error waiting for response from Deoxys node");
-
-    assert_eq!(response_deoxys.block_number, response_expected);
-    assert_eq!(response_deoxys, response_pathfinder);
+    let response_deoxys = &responses[DEOXYS];
+    assert_eq!(response_deoxys.block_number, tip.block_number);
+    assert_eq!(response_deoxys.block_hash, tip.block_hash);
+    assert_eq!(response_deoxys, &responses[PATHFINDER]);
 }
@@ -0,0 +1,95 @@
+mod common;
+use common::*;
+
+use std::collections::HashMap;
+
+use starknet_providers::{JsonRpcClient, Provider};
+use unit_tests::{
+    equivalence::{check_block_id_equivalence, Probe},
+    sampling::{Sampler, Strategy},
+};
+
+async fn assert_equivalent(
+    clients: &HashMap<String, JsonRpcClient<DittoTransport>>,
+    block_number: Option<u64>,
+) {
+    let probe = Probe::default();
+
+    for (name, client) in clients.iter() {
+        let block_number = match block_number {
+            Some(block_number) => block_number,
+            None => client
+                .block_number()
+                .await
+                .unwrap_or_else(|e| panic!("{name}: {e}")),
+        };
+
+        let mismatches = check_block_id_equivalence(client, block_number, &probe)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        for mismatch in mismatches.iter() {
+            println!("{name}: {mismatch}");
+        }
+        assert!(mismatches.is_empty());
+    }
+}
+
+///
+/// Block id equivalence for every block-scoped method
+///
+/// purpose: check that a block number and its block hash are interchangeable.
+/// success case: every method returns the same result for both forms of the block id.
+///
+#[require(block_min = 300_000, spec_version = "0.5.1")]
+#[rstest]
+#[case::block_0(0)]
+#[case::block_legacy(BLOCK_LEGACY)]
+#[case::block_50000(50_000)]
+#[case::block_300000(300_000)]
+#[tokio::test]
+async fn work_with_block(
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
+    #[case] block_number: u64,
+) {
+    assert_equivalent(&clients, Some(block_number)).await;
+}
+
+///
+/// Block id equivalence for every block-scoped method at the tip
+///
+/// purpose: check that a block number, its block hash and the `latest` tag are
+///          interchangeable at the tip.
+/// success case: every method returns the same result for all three forms of the block id.
+///
+#[require(spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_with_latest(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
+    assert_equivalent(&clients, None).await;
+}
+
+///
+/// Block id equivalence for every block-scoped method
+///
+/// purpose: check that a block number and its block hash are interchangeable in every
+///          Starknet version.
+/// success case: every method returns the same result for both forms of the block id.
+///
+#[require(spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+#[logging]
+async fn work_with_sampled_blocks(
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
+    mut sampler: Sampler,
+) {
+    let blocks = sampler
+        .sample(&clients[DEOXYS], Strategy::StratifiedByVersion, 8)
+        .await
+        .expect(ERR_DEOXYS);
+
+    for block_number in blocks {
+        assert_equivalent(&clients, Some(block_number)).await;
+    }
+}
@@ -3,10 +3,7 @@ use common::*;
 
 use std::collections::HashMap;
 
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider,
-};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider};
 
 ///
 /// Unit test for `starknet_blockNumber`
@@ -17,7 +14,7 @@ use starknet_providers::{
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
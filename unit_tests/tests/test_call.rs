@@ -9,7 +9,7 @@ use starknet_core::{
     types::{BlockId, BlockTag, FieldElement, FunctionCall, StarknetError},
     utils::get_selector_from_name,
 };
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 
 ///
 /// Unit test for `starknet_call`
@@ -20,7 +20,7 @@ use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, Provid
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -50,7 +50,7 @@ async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_contract(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_contract(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -83,7 +83,7 @@ async fn fail_non_existing_contract(clients: HashMap<String, JsonRpcClient<HttpT
 #[rstest]
 #[tokio::test]
 async fn fail_invalid_contract_entry_point_selector(
-    clients: HashMap<String, JsonRpcClient<HttpTransport>>,
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
 ) {
     let deoxys = &clients[DEOXYS];
 
@@ -116,7 +116,7 @@ async fn fail_invalid_contract_entry_point_selector(
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_missing_contract_call_data(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_missing_contract_call_data(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -148,7 +148,7 @@ async fn fail_missing_contract_call_data(clients: HashMap<String, JsonRpcClient<
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_invalid_contract_call_data(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_invalid_contract_call_data(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -178,7 +178,7 @@ async fn fail_invalid_contract_call_data(clients: HashMap<String, JsonRpcClient<
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_too_many_call_data(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_too_many_call_data(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -208,7 +208,7 @@ async fn fail_too_many_call_data(clients: HashMap<String, JsonRpcClient<HttpTran
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_correct_call(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_correct_call(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -251,7 +251,7 @@ async fn work_correct_call(clients: HashMap<String, JsonRpcClient<HttpTransport>
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_correct_call_with_args(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_correct_call_with_args(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -294,7 +294,7 @@ async fn work_correct_call_with_args(clients: HashMap<String, JsonRpcClient<Http
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_with_multiple_args(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_with_multiple_args(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
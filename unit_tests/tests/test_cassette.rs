@@ -0,0 +1,90 @@
+mod common;
+use common::*;
+
+use std::{fs, path::Path};
+
+use serde_json::json;
+use starknet_providers::{JsonRpcClient, Provider};
+use unit_tests::{
+    batch::Request,
+    cassette::{self, cassette_path, Cassette},
+    replay::CapturedCall,
+    transport::{RawResponse, RpcObserver},
+};
+
+fn block_number(timestamp_ms: u64, block_number: u64) -> CapturedCall {
+    CapturedCall {
+        timestamp_ms,
+        request: Request::new("starknet_blockNumber", json!([])),
+        response: Some(RawResponse::Success {
+            result: json!(block_number),
+        }),
+    }
+}
+
+///
+/// Cassettes
+///
+/// purpose: locate the cassette of the running test.
+/// success case: cassettes are stored by test binary, test and node.
+///
+#[rstest]
+fn work_cassette_path() {
+    let path = cassette_path(DEOXYS).expect("Tests run on a thread named after them");
+
+    assert_eq!(
+        path,
+        Path::new("cassettes/test_cassette/work_cassette_path/deoxys.jsonl")
+    );
+}
+
+///
+/// Cassettes
+///
+/// purpose: record calls to a cassette then load it back.
+/// success case: the spec version the cassette was recorded with is saved with the calls.
+///
+#[rstest]
+fn work_record_cassette() {
+    let path = std::env::temp_dir()
+        .join(format!("ditto-cassette-{}", std::process::id()))
+        .join("deoxys.jsonl");
+
+    let recorder = cassette::record(&path, "0.5.1").expect("Failed to record cassette");
+    let call = block_number(0, 50000);
+    recorder
+        .observe(
+            &call.request.method,
+            &call.request.params,
+            call.response.as_ref().unwrap(),
+        )
+        .expect("Failed to record call");
+
+    let cassette = Cassette::load(&path).expect("Failed to load cassette");
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    assert_eq!(cassette.spec_version(), Some(String::from("0.5.1")));
+    assert_eq!(
+        cassette.response("starknet_blockNumber", &json!([])),
+        call.response
+    );
+}
+
+///
+/// Cassettes
+///
+/// purpose: answer calls from a cassette, without a node.
+/// success case: repeated calls get their recorded responses in order, then the last one,
+///               calls which were not recorded fail.
+///
+#[rstest]
+#[tokio::test]
+async fn work_replay_cassette() {
+    let cassette = Cassette::new(vec![block_number(0, 50000), block_number(1, 50001)]);
+    let client = JsonRpcClient::new(DittoTransport::replay(cassette));
+
+    assert_eq!(client.block_number().await.unwrap(), 50000);
+    assert_eq!(client.block_number().await.unwrap(), 50001);
+    assert_eq!(client.block_number().await.unwrap(), 50001);
+    assert!(client.chain_id().await.is_err());
+}
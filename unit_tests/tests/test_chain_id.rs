@@ -1,7 +1,7 @@
 mod common;
 use common::*;
 
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet_providers::{JsonRpcClient, Provider};
 use std::collections::HashMap;
 
 ///
@@ -13,7 +13,7 @@ use std::collections::HashMap;
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn chain_id(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn chain_id(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -0,0 +1,92 @@
+#![feature(assert_matches)]
+
+mod common;
+use common::*;
+
+use std::collections::HashMap;
+
+use starknet_core::types::{BlockId, BlockTag, ContractClass, FieldElement};
+use starknet_providers::{JsonRpcClient, Provider};
+use unit_tests::class_hash::{compute_class_hash, verify_class, verify_class_at};
+
+///
+/// Class hash verification for `starknet_getClassAt` and `starknet_getClass`
+///
+/// purpose: recompute the class hash of a legacy (Cairo 0) class.
+/// success case: the class hashes to the value returned by `starknet_getClassHashAt` on all nodes.
+///
+#[require(block_min = 2891, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_contract_v0(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
+    let block_id = BlockId::Number(BLOCK_LEGACY);
+    let contract_address = FieldElement::from_hex_be(CONTRACT_LEGACY).unwrap();
+
+    for (name, client) in clients.iter() {
+        let class_hash = verify_class_at(client, block_id, contract_address)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        verify_class(client, block_id, class_hash)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+    }
+}
+
+///
+/// Class hash verification for `starknet_getClassAt` and `starknet_getClass`
+///
+/// purpose: recompute the class hash of a Sierra (Cairo 1) class.
+/// success case: the class hashes to the value returned by `starknet_getClassHashAt` on all nodes.
+///
+#[require(block_min = 500_000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_contract_v1(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
+    let block_id = BlockId::Tag(BlockTag::Latest);
+    let contract_address = FieldElement::from_hex_be(CONTRACT_ACCOUNT).unwrap();
+
+    for (name, client) in clients.iter() {
+        let class_hash = verify_class_at(client, block_id, contract_address)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        verify_class(client, block_id, class_hash)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+    }
+}
+
+///
+/// Class hash verification for `starknet_getClassAt`
+///
+/// purpose: make sure the verifier catches a class which does not match its hash.
+/// fail case: tampered legacy bytecode no longer hashes to the contract's class hash.
+///
+#[require(block_min = 2891, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn fail_tampered_class(deoxys: JsonRpcClient<DittoTransport>) {
+    let block_id = BlockId::Number(BLOCK_LEGACY);
+    let contract_address = FieldElement::from_hex_be(CONTRACT_LEGACY).unwrap();
+
+    let class_hash = deoxys
+        .get_class_hash_at(block_id, contract_address)
+        .await
+        .expect(ERR_DEOXYS);
+    let mut class = deoxys
+        .get_class_at(block_id, contract_address)
+        .await
+        .expect(ERR_DEOXYS);
+
+    match &mut class {
+        ContractClass::Legacy(class) => {
+            class.entry_points_by_type.external.reverse();
+            class.entry_points_by_type.external[0].offset += 1;
+        }
+        ContractClass::Sierra(_) => panic!("Expected legacy contract"),
+    }
+
+    let computed = compute_class_hash(&class).expect("Failed to compute class hash");
+    assert_ne!(computed, class_hash);
+}
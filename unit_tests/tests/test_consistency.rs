@@ -0,0 +1,86 @@
+mod common;
+use common::*;
+
+use std::collections::HashMap;
+
+use starknet_providers::JsonRpcClient;
+use unit_tests::{
+    consistency::{check_block, check_range},
+    sampling::{Sampler, Strategy},
+};
+
+///
+/// Cross-method consistency for `starknet_getBlockTransactionCount`, `starknet_getBlockWithTxHashes`,
+/// `starknet_getBlockWithTxs`, `starknet_getTransactionByBlockIdAndIndex`,
+/// `starknet_getTransactionByHash` and `starknet_getTransactionReceipt`
+///
+/// purpose: check that all block and transaction methods agree on a single node.
+/// success case: no inconsistency on any node.
+///
+#[require(block_min = 50_000, spec_version = "0.5.1")]
+#[rstest]
+#[case::block_0(0)]
+#[case::block_3800(3800)]
+#[case::block_50000(50_000)]
+#[tokio::test]
+async fn work_with_block(
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
+    #[case] block_number: u64,
+) {
+    for (name, client) in clients.iter() {
+        let inconsistencies = check_block(client, block_number)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        for inconsistency in inconsistencies.iter() {
+            println!("{name}: {inconsistency}");
+        }
+        assert!(inconsistencies.is_empty());
+    }
+}
+
+///
+/// Cross-method consistency on busy blocks
+///
+/// purpose: check that all block and transaction methods agree on blocks with many
+///          transactions.
+/// success case: no inconsistency on any node.
+///
+#[require(spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+#[logging]
+async fn work_with_busy_blocks(deoxys: JsonRpcClient<DittoTransport>, mut sampler: Sampler) {
+    let blocks = sampler
+        .sample(&deoxys, Strategy::BusyBlocks, 3)
+        .await
+        .expect(ERR_DEOXYS);
+
+    for block_number in blocks {
+        let inconsistencies = check_block(&deoxys, block_number).await.expect(ERR_DEOXYS);
+
+        for inconsistency in inconsistencies.iter() {
+            println!("{inconsistency}");
+        }
+        assert!(inconsistencies.is_empty());
+    }
+}
+
+///
+/// Cross-method consistency over a range of blocks
+///
+/// purpose: check that all block and transaction methods agree on a single node.
+/// success case: no inconsistency over the first 1000 blocks.
+///
+#[require(block_min = 1000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+#[ignore = "slow, checks every transaction in the range"]
+async fn work_with_range(deoxys: JsonRpcClient<DittoTransport>) {
+    let inconsistencies = check_range(&deoxys, 0..=1000).await.expect(ERR_DEOXYS);
+
+    for inconsistency in inconsistencies.iter() {
+        println!("{inconsistency}");
+    }
+    assert!(inconsistencies.is_empty());
+}
@@ -0,0 +1,123 @@
+mod common;
+use common::*;
+
+use serde_json::json;
+use unit_tests::{
+    coverage::{BlockIdForm, CoverageMatrix, Hit},
+    spec::OpenRpcSpec,
+    transport::{RawError, RawResponse},
+};
+
+fn success() -> RawResponse {
+    RawResponse::Success { result: json!({}) }
+}
+
+fn error(code: i64) -> RawResponse {
+    RawResponse::Error {
+        error: RawError {
+            code,
+            message: String::new(),
+            data: None,
+        },
+    }
+}
+
+///
+/// Spec coverage of the test suite
+///
+/// purpose: extract block ids from the params of a call.
+/// success case: block ids are found as top-level params and inside event filters.
+///
+#[rstest]
+fn work_hit_block_ids() {
+    let hit = Hit::new(
+        "starknet_getStorageAt",
+        &json!({ "contract_address": "0x1", "key": "0x2", "block_id": { "block_number": 1 } }),
+        &success(),
+    );
+    assert_eq!(hit.block_ids, vec![BlockIdForm::Number]);
+    assert_eq!(hit.params.len(), 3);
+
+    let hit = Hit::new(
+        "starknet_getEvents",
+        &json!({ "filter": { "from_block": "latest", "to_block": { "block_hash": "0x1" }, "chunk_size": 10 } }),
+        &success(),
+    );
+    assert_eq!(hit.block_ids, vec![BlockIdForm::Latest, BlockIdForm::Hash]);
+}
+
+///
+/// Spec coverage of the test suite
+///
+/// purpose: aggregate calls into a coverage matrix.
+/// success case: block id forms, params and errors are counted per method.
+///
+#[rstest]
+fn work_matrix() {
+    let spec = OpenRpcSpec::load(SPEC_0_5_1).expect("Failed to load spec");
+    let hits = vec![
+        Hit::new(
+            "starknet_getBlockWithTxs",
+            &json!({ "block_id": { "block_hash": "0x1" } }),
+            &success(),
+        ),
+        Hit::new(
+            "starknet_getBlockWithTxs",
+            &json!({ "block_id": "pending" }),
+            &success(),
+        ),
+        Hit::new(
+            "starknet_getBlockWithTxs",
+            &json!({ "block_id": { "block_hash": "0x0" } }),
+            &error(24),
+        ),
+    ];
+
+    let matrix = CoverageMatrix::new(&spec, &hits);
+    let coverage = matrix
+        .methods
+        .iter()
+        .find(|m| m.method == "starknet_getBlockWithTxs")
+        .unwrap();
+    let block_ids = coverage.block_ids.as_ref().unwrap();
+
+    assert_eq!(coverage.calls, 3);
+    assert_eq!(coverage.successes, 2);
+    assert_eq!(block_ids[&BlockIdForm::Hash], 2);
+    assert_eq!(block_ids[&BlockIdForm::Pending], 1);
+    assert_eq!(block_ids[&BlockIdForm::Number], 0);
+    assert_eq!(coverage.params["block_id"], 3);
+    assert!(coverage
+        .errors
+        .iter()
+        .any(|(e, n)| e.name == "BLOCK_NOT_FOUND" && *n == 1));
+
+    let coverage = matrix
+        .methods
+        .iter()
+        .find(|m| m.method == "starknet_chainId")
+        .unwrap();
+    assert!(coverage.block_ids.is_none());
+    assert_eq!(coverage.calls, 0);
+}
+
+///
+/// Spec coverage of the test suite
+///
+/// purpose: aggregate an error which the spec does not list for the method.
+/// fail case: error is reported as unlisted.
+///
+#[rstest]
+fn fail_unlisted_error() {
+    let spec = OpenRpcSpec::load(SPEC_0_5_1).expect("Failed to load spec");
+    let hits = vec![Hit::new("starknet_blockNumber", &json!({}), &error(24))];
+
+    let matrix = CoverageMatrix::new(&spec, &hits);
+    let coverage = matrix
+        .methods
+        .iter()
+        .find(|m| m.method == "starknet_blockNumber")
+        .unwrap();
+
+    assert_eq!(coverage.unlisted_errors[&24], 1);
+}
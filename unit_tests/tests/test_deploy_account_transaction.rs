@@ -5,10 +5,7 @@ use common::*;
 use starknet_core::types::{
     BroadcastedDeployAccountTransaction, FieldElement, StarknetError, TransactionStatus,
 };
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
-};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
 use std::assert_matches::assert_matches;
 use std::thread;
 use std::time::Duration;
@@ -49,7 +46,7 @@ use std::time::Duration;
 
 #[rstest]
 #[tokio::test]
-async fn fail_if_param_(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_if_param_(deoxys: JsonRpcClient<DittoTransport>) {
     let invalid_deploy_account_transaction = BroadcastedDeployAccountTransaction {
         max_fee: FieldElement::from_hex_be("0x0ffffffff").unwrap(),
         signature: vec![FieldElement::from_hex_be("signature_array").unwrap()],
@@ -74,7 +71,7 @@ async fn fail_if_param_(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn fail_if_insufficient_max_fee(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_if_insufficient_max_fee(deoxys: JsonRpcClient<DittoTransport>) {
     let invalid_deploy_account_transaction = BroadcastedDeployAccountTransaction {
         max_fee: FieldElement::from_hex_be("0x000000").unwrap(), //here max_fee is insufficient
         signature: vec![FieldElement::from_hex_be("signature_array").unwrap()],
@@ -99,7 +96,7 @@ async fn fail_if_insufficient_max_fee(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn fail_if_invalid_transaction_nonce(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_if_invalid_transaction_nonce(deoxys: JsonRpcClient<DittoTransport>) {
     let invalid_deploy_account_transaction = BroadcastedDeployAccountTransaction {
         max_fee: FieldElement::from_hex_be("0x0ffffffff").unwrap(),
         signature: vec![FieldElement::from_hex_be("signature_array").unwrap()],
@@ -124,7 +121,7 @@ async fn fail_if_invalid_transaction_nonce(deoxys: JsonRpcClient<HttpTransport>)
 
 #[rstest]
 #[tokio::test]
-async fn works_ok(deoxys: JsonRpcClient<HttpTransport>) {
+async fn works_ok(deoxys: JsonRpcClient<DittoTransport>) {
     let valid_deploy_account_transaction = BroadcastedDeployAccountTransaction {
         max_fee: FieldElement::from_hex_be("0x0ffffffff").unwrap(),
         signature: vec![FieldElement::from_hex_be("signature_array").unwrap()],
@@ -0,0 +1,241 @@
+mod common;
+use common::*;
+
+use serde_json::{json, Value};
+use starknet_core::types::{FieldElement, StateDiff};
+use starknet_providers::JsonRpcClient;
+use unit_tests::{
+    batch::Request,
+    cassette::Cassette,
+    drilldown::{drilldown, state_entries, StateDivergence, StateEntry},
+    replay::CapturedCall,
+    transport::RawResponse,
+};
+
+fn call(method: &str, params: Value, result: Value) -> CapturedCall {
+    CapturedCall {
+        timestamp_ms: 0,
+        request: Request::new(method, params),
+        response: Some(RawResponse::Success { result }),
+    }
+}
+
+///
+/// Node whose state update of block 5 is `state_diff`, with the storage value of contract
+/// 0x1 at key 0x2, the nonce of contract 0x9 and the class hash of contract 0x1 at blocks 5
+/// and 4 given as `[storage, nonce, class_hash]`.
+///
+fn node(
+    state_diff: Value,
+    at_block: [&str; 3],
+    at_parent: [&str; 3],
+) -> JsonRpcClient<DittoTransport> {
+    let mut calls = vec![call(
+        "starknet_getStateUpdate",
+        json!({ "block_id": { "block_number": 5 } }),
+        json!({
+            "block_hash": "0xa",
+            "new_root": "0x1",
+            "old_root": "0x2",
+            "state_diff": state_diff
+        }),
+    )];
+
+    for (block_number, [storage, nonce, class_hash]) in [(5, at_block), (4, at_parent)] {
+        let block_id = json!({ "block_number": block_number });
+        calls.extend([
+            call(
+                "starknet_getStorageAt",
+                json!({ "contract_address": "0x1", "key": "0x2", "block_id": block_id }),
+                json!(storage),
+            ),
+            call(
+                "starknet_getNonce",
+                json!({ "block_id": block_id, "contract_address": "0x9" }),
+                json!(nonce),
+            ),
+            call(
+                "starknet_getClassHashAt",
+                json!({ "block_id": block_id, "contract_address": "0x1" }),
+                json!(class_hash),
+            ),
+        ]);
+    }
+
+    JsonRpcClient::new(DittoTransport::replay(Cassette::new(calls)))
+}
+
+///
+/// State diff drill-down
+///
+/// purpose: flatten a state diff into single state entries.
+/// success case: every storage write, nonce, class and declared class is an entry.
+///
+#[rstest]
+fn work_state_entries() {
+    let state_diff: StateDiff = serde_json::from_value(json!({
+        "storage_diffs": [{
+            "address": "0x1",
+            "storage_entries": [{ "key": "0x2", "value": "0x3" }, { "key": "0x4", "value": "0x5" }]
+        }],
+        "deprecated_declared_classes": ["0x6"],
+        "declared_classes": [{ "class_hash": "0x7", "compiled_class_hash": "0x8" }],
+        "deployed_contracts": [{ "address": "0x9", "class_hash": "0xa" }],
+        "replaced_classes": [{ "contract_address": "0x1", "class_hash": "0xb" }],
+        "nonces": [{ "contract_address": "0x9", "nonce": "0x1" }]
+    }))
+    .unwrap();
+
+    let felt = |n: u64| FieldElement::from(n);
+    let entries = state_entries(&state_diff);
+
+    assert_eq!(entries.len(), 7);
+    assert_eq!(
+        entries[&StateEntry::Storage {
+            contract: felt(1),
+            key: felt(4)
+        }],
+        felt(5)
+    );
+    assert_eq!(
+        entries[&StateEntry::ClassHash { contract: felt(1) }],
+        felt(0xb)
+    );
+    assert_eq!(entries[&StateEntry::Nonce { contract: felt(9) }], felt(1));
+    assert_eq!(
+        entries[&StateEntry::CompiledClassHash {
+            class_hash: felt(6)
+        }],
+        FieldElement::ZERO
+    );
+}
+
+///
+/// State diff drill-down
+///
+/// purpose: report an entry which only differs in the state diffs.
+/// success case: the divergence is flagged as unresolved, with the values of the state diffs.
+///
+#[rstest]
+fn work_unresolved_divergence() {
+    let divergence = StateDivergence {
+        entry: StateEntry::Nonce {
+            contract: FieldElement::ONE,
+        },
+        expected: Some(FieldElement::ONE),
+        actual: None,
+        inherited: false,
+        unresolved: true,
+    };
+
+    assert_eq!(
+        divergence.to_string(),
+        format!(
+            "(0x{:064x}, nonce, 0x{:064x}, none) in state diffs only",
+            1, 1
+        )
+    );
+}
+
+///
+/// State diff drill-down
+///
+/// purpose: classify the entries on which the state diffs of two nodes differ.
+/// success case: a storage write is new at the block, a nonce already differed at the
+///               parent block, a replaced class with the same class hash on both nodes is
+///               unresolved and a missing declared class is reported from the state diffs.
+///
+#[rstest]
+#[tokio::test]
+async fn work_drilldown_divergences() {
+    let state_diff = |storage: &str, nonce: &str, replaced: Value, declared: Value| {
+        json!({
+            "storage_diffs": [{
+                "address": "0x1",
+                "storage_entries": [{ "key": "0x2", "value": storage }]
+            }],
+            "deprecated_declared_classes": [],
+            "declared_classes": declared,
+            "deployed_contracts": [],
+            "replaced_classes": replaced,
+            "nonces": [{ "contract_address": "0x9", "nonce": nonce }]
+        })
+    };
+
+    let deoxys = node(
+        state_diff("0x3", "0x2", json!([]), json!([])),
+        ["0x3", "0x2", "0xb"],
+        ["0x0", "0x1", "0xa"],
+    );
+    let pathfinder = node(
+        state_diff(
+            "0x4",
+            "0x1",
+            json!([{ "contract_address": "0x1", "class_hash": "0xb" }]),
+            json!([{ "class_hash": "0x7", "compiled_class_hash": "0x8" }]),
+        ),
+        ["0x4", "0x1", "0xb"],
+        ["0x0", "0x0", "0xa"],
+    );
+
+    let divergences = drilldown(&deoxys, &pathfinder, 5)
+        .await
+        .expect("Error while drilling down the state diffs");
+    for divergence in divergences.iter() {
+        println!("{divergence}");
+    }
+
+    let felt = |n: u64| FieldElement::from(n);
+    let divergence = |entry: StateEntry| {
+        divergences
+            .iter()
+            .find(|divergence| divergence.entry == entry)
+            .unwrap_or_else(|| panic!("No divergence on {entry:?}"))
+    };
+    assert_eq!(divergences.len(), 4);
+
+    let storage = divergence(StateEntry::Storage {
+        contract: felt(1),
+        key: felt(2),
+    });
+    assert_eq!(
+        (storage.expected, storage.actual),
+        (Some(felt(4)), Some(felt(3)))
+    );
+    assert!(!storage.inherited && !storage.unresolved);
+
+    let nonce = divergence(StateEntry::Nonce { contract: felt(9) });
+    assert_eq!(
+        (nonce.expected, nonce.actual),
+        (Some(felt(1)), Some(felt(2)))
+    );
+    assert!(nonce.inherited && !nonce.unresolved);
+
+    let class_hash = divergence(StateEntry::ClassHash { contract: felt(1) });
+    assert_eq!(
+        (class_hash.expected, class_hash.actual),
+        (Some(felt(0xb)), None)
+    );
+    assert!(class_hash.unresolved);
+
+    let declared = divergence(StateEntry::CompiledClassHash {
+        class_hash: felt(7),
+    });
+    assert_eq!((declared.expected, declared.actual), (Some(felt(8)), None));
+    assert!(!declared.unresolved);
+}
+
+///
+/// State diff drill-down
+///
+/// purpose: a node never diverges from itself.
+/// success case: no state entry differs when a block is compared with the same node.
+///
+#[require(block_min = 50_000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_same_node(deoxys: JsonRpcClient<DittoTransport>) {
+    let divergences = drilldown(&deoxys, &deoxys, 50_000).await.expect(ERR_DEOXYS);
+
+    assert!(divergences.is_empty());
+}
@@ -0,0 +1,92 @@
+mod common;
+use common::*;
+
+use starknet_providers::{JsonRpcClient, Provider};
+use unit_tests::eras::{boundary_blocks, check_boundaries, find_eras, Era};
+
+fn era(version: &str, start: u64, end: u64) -> Era {
+    Era {
+        version: version.to_string(),
+        start,
+        end,
+    }
+}
+
+///
+/// Starknet version eras
+///
+/// purpose: list the blocks around each era boundary.
+/// success case: `margin` blocks on each side, clamped to eras shorter than the margin.
+///
+#[rstest]
+fn work_boundary_blocks() {
+    let eras = [
+        era("", 0, 3799),
+        era("0.9.1", 3800, 3800),
+        era("0.10.0", 3801, 10_000),
+    ];
+
+    assert_eq!(
+        boundary_blocks(&eras, 2),
+        vec![3798, 3799, 3800, 3801, 3802]
+    );
+    assert!(boundary_blocks(&eras, 0).is_empty());
+}
+
+///
+/// Starknet version eras
+///
+/// purpose: locate the first block of each Starknet version.
+/// success case: eras are contiguous, have distinct versions and block 3800 starts the
+///               first versioned era.
+///
+#[require(block_min = 3800, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_find_eras(deoxys: JsonRpcClient<DittoTransport>) {
+    let tip = deoxys.block_number().await.expect(ERR_DEOXYS);
+    let eras = find_eras(&deoxys, 0..=tip).await.expect(ERR_DEOXYS);
+
+    for era in eras.iter() {
+        println!("{era}");
+    }
+
+    assert_eq!(eras.first().map(|era| era.start), Some(0));
+    assert_eq!(eras.last().map(|era| era.end), Some(tip));
+    assert!(eras
+        .windows(2)
+        .all(|pair| pair[0].end + 1 == pair[1].start && pair[0].version != pair[1].version));
+    assert!(eras.iter().any(|era| era.start == 3800));
+}
+
+///
+/// Starknet version eras
+///
+/// purpose: run every comparison on the blocks just before and after each version boundary.
+/// success case: both nodes agree on the eras and no comparison fails around boundaries.
+///
+#[require(block_min = 3800, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_around_boundaries(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let tip = deoxys
+        .block_number()
+        .await
+        .expect(ERR_DEOXYS)
+        .min(pathfinder.block_number().await.expect(ERR_PATHFINDER));
+
+    let eras = find_eras(&pathfinder, 0..=tip).await.expect(ERR_PATHFINDER);
+    assert_eq!(find_eras(&deoxys, 0..=tip).await.expect(ERR_DEOXYS), eras);
+
+    let findings = check_boundaries(&deoxys, &pathfinder, &eras, 1)
+        .await
+        .expect("Error while checking era boundaries");
+
+    for finding in findings.iter() {
+        println!("{finding}");
+    }
+    assert!(findings.is_empty());
+}
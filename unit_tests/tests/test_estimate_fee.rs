@@ -4,10 +4,7 @@ mod common;
 use common::*;
 
 use starknet_core::types::{BlockId, BlockTag, FieldElement, StarknetError};
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
-};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
 use std::assert_matches::assert_matches;
 use std::collections::HashMap;
 use unit_tests::{BadTransactionFactory, OkTransactionFactory, TransactionFactory};
@@ -15,7 +12,7 @@ use unit_tests::{BadTransactionFactory, OkTransactionFactory, TransactionFactory
 #[rstest]
 #[tokio::test]
 #[ignore = "Fix failing unwrap due to empty constant"]
-async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let ok_invoke_transaction = OkTransactionFactory::build(Some(FieldElement::ZERO));
@@ -38,7 +35,7 @@ async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[tokio::test]
 #[ignore = "Fix failing unwrap due to empty constant"]
 async fn fail_if_one_txn_cannot_be_executed(
-    clients: HashMap<String, JsonRpcClient<HttpTransport>>,
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
 ) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
@@ -66,7 +63,7 @@ async fn fail_if_one_txn_cannot_be_executed(
 #[rstest]
 #[tokio::test]
 #[ignore = "Fix failing unwrap due to empty constant"]
-async fn works_ok(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn works_ok(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
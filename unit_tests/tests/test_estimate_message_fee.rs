@@ -3,10 +3,7 @@
 mod common;
 use common::*;
 use starknet_core::types::{BlockId, BlockTag, EthAddress, FieldElement, MsgFromL1, StarknetError};
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
-};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
 use std::assert_matches::assert_matches;
 
 /// Test for the `get_state_update` Deoxys RPC method
@@ -44,7 +41,7 @@ pub fn get_message_from_l1(
 #[require(block_min = 200_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_block(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_non_existing_block(deoxys: JsonRpcClient<DittoTransport>) {
     let payload_message: Vec<FieldElement> = vec![];
     let contract_address = FieldElement::from_hex_be(
         "0x049D36570D4e46f48e99674bd3fcc84644DdD6b96F7C741B1562B82f9e004dC7",
@@ -69,7 +66,7 @@ async fn fail_non_existing_block(deoxys: JsonRpcClient<HttpTransport>) {
 #[require(block_min = 200_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_contract_not_found(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_contract_not_found(deoxys: JsonRpcClient<DittoTransport>) {
     let unknown_contract_address =
         FieldElement::from_hex_be("0x4269DEADBEEF").expect("Invalid Contract Address");
     let payload_message: Vec<FieldElement> = vec![];
@@ -94,7 +91,7 @@ async fn fail_contract_not_found(deoxys: JsonRpcClient<HttpTransport>) {
 #[require(block_min = 200_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_contract_error(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_contract_error(deoxys: JsonRpcClient<DittoTransport>) {
     //On this test, the contract address must be valid,
     //but the from_address, entry_point_selector or the payload must be invalid
     let payload_message: Vec<FieldElement> = vec![];
@@ -124,8 +121,8 @@ async fn fail_contract_error(deoxys: JsonRpcClient<HttpTransport>) {
 #[rstest]
 #[tokio::test]
 async fn estimate_message_fee_works_ok(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     let contract_address = FieldElement::from_hex_be(
         "0x073314940630fd6dcda0d772d4c972c4e0a9946bef9dabf4ef84eda8ef542b82",
@@ -0,0 +1,86 @@
+mod common;
+use common::*;
+
+use std::{collections::BTreeMap, time::Duration};
+
+use starknet_providers::JsonRpcClient;
+use unit_tests::bench::{
+    pagination::{drain, Drain, PaginationResult, PaginationWorkload},
+    Stats,
+};
+
+fn drained(events: u64, complete: bool) -> Drain {
+    let mut pages = Stats::new();
+    pages.record(Duration::from_millis(100));
+    pages.elapsed = Duration::from_secs(2);
+
+    Drain {
+        events,
+        pages,
+        time_to_first_page: Duration::from_millis(100),
+        complete,
+    }
+}
+
+///
+/// getEvents pagination
+///
+/// purpose: build one query per filter and chunk size, and compare drains across nodes.
+/// success case: event counts only differ if both drains went through every page.
+///
+#[rstest]
+fn work_queries_and_mismatch() {
+    let workload = PaginationWorkload {
+        chunk_sizes: vec![10, 100],
+        ..PaginationWorkload::new(50_000, 50_010)
+    };
+    let queries = workload.queries();
+    assert_eq!(queries.len(), 8);
+    assert!(queries
+        .iter()
+        .all(|(_, query)| query.from_block == 50_000 && query.to_block == 50_010));
+
+    assert_eq!(drained(200, true).events_per_second(), 100.0);
+
+    let result = |pathfinder| PaginationResult {
+        filter: String::from("none"),
+        chunk_size: 10,
+        drains: BTreeMap::from([
+            (String::from(DEOXYS), drained(200, true)),
+            (String::from(PATHFINDER), pathfinder),
+        ]),
+    };
+    assert!(!result(drained(200, true)).is_mismatch());
+    assert!(result(drained(150, true)).is_mismatch());
+    assert!(!result(drained(150, false)).is_mismatch());
+}
+
+///
+/// getEvents pagination
+///
+/// purpose: drain a block range by following continuation tokens on each node.
+/// success case: both nodes return the same number of events, however they split them
+///               into pages.
+///
+#[require(block_min = 50_010, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_drain(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let (_, query) = PaginationWorkload::new(50_000, 50_010)
+        .queries()
+        .into_iter()
+        .find(|(filter, query)| *filter == "keys" && query.chunk_size == 100)
+        .unwrap();
+
+    let drain_deoxys = drain(&deoxys, &query, None).await.expect(ERR_DEOXYS);
+    let drain_pathfinder = drain(&pathfinder, &query, None)
+        .await
+        .expect(ERR_PATHFINDER);
+
+    assert!(drain_deoxys.complete);
+    assert!(drain_deoxys.time_to_first_page <= drain_deoxys.pages.elapsed);
+    assert_eq!(drain_deoxys.events, drain_pathfinder.events);
+}
@@ -4,19 +4,20 @@
 mod common;
 use common::*;
 
-use starknet_core::types::{BlockId, BlockTag, FieldElement, StarknetError};
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
-};
-use std::sync::Arc;
+use starknet_core::types::{BlockId, FieldElement, StarknetError};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
 use std::{assert_matches::assert_matches, collections::HashMap};
-use unit_tests::constants::DEOXYS;
+use unit_tests::{
+    constants::DEOXYS,
+    sampling::{Sampler, Strategy},
+    sweep::{sweep, Component, SweepOptions},
+    tip::{latest, TipOptions},
+};
 
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -33,28 +34,28 @@ async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_with_latest_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
-    let deoxys = &clients[DEOXYS];
-    let pathfinder = &clients[PATHFINDER];
-
-    let block_tag = BlockId::Tag(BlockTag::Latest);
-
-    let response_deoxys = deoxys
-        .get_block_transaction_count(block_tag)
-        .await
-        .expect("Error waiting for response from Deoxys node");
-
-    let response_pathfinder = pathfinder
-        .get_block_transaction_count(block_tag)
-        .await
-        .expect("Error waiting for response from Pathfinder node");
+async fn work_with_latest_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
+    let (tip, responses) = latest(&clients, &TipOptions::default(), |client, block_id| {
+        client.get_block_transaction_count(block_id)
+    })
+    .await
+    .expect("Error waiting for response from nodes");
+
+    let block_id = BlockId::Hash(tip.block_hash);
+    for (name, client) in clients.iter() {
+        let response = client
+            .get_block_transaction_count(block_id)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(responses[name], response, "{name}");
+    }
 
-    assert_eq!(response_deoxys, response_pathfinder);
+    assert_eq!(responses[DEOXYS], responses[PATHFINDER]);
 }
 
 async fn work_with_block(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
     block_number: u64,
 ) {
     let block_number = BlockId::Number(block_number);
@@ -76,8 +77,8 @@ async fn work_with_block(
 #[rstest]
 #[tokio::test]
 async fn work_with_block_1(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_block(deoxys, pathfinder, 1).await;
 }
@@ -85,7 +86,7 @@ async fn work_with_block_1(
 #[require(block_min = 1, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_with_block_1_hash(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_with_block_1_hash(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -114,8 +115,8 @@ async fn work_with_block_1_hash(clients: HashMap<String, JsonRpcClient<HttpTrans
 #[rstest]
 #[tokio::test]
 async fn work_with_block_5066(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_block(deoxys, pathfinder, 1).await;
 }
@@ -124,8 +125,8 @@ async fn work_with_block_5066(
 #[rstest]
 #[tokio::test]
 async fn work_with_block_100_000(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_block(deoxys, pathfinder, 100_000).await;
 }
@@ -133,7 +134,7 @@ async fn work_with_block_100_000(
 #[require(block_min = 100_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_with_block_100_000_hash(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_with_block_100_000_hash(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -161,53 +162,53 @@ async fn work_with_block_100_000_hash(clients: HashMap<String, JsonRpcClient<Htt
 #[rstest]
 #[tokio::test]
 #[ignore = "ignore this test"]
-async fn work_loop(deoxys: JsonRpcClient<HttpTransport>, pathfinder: JsonRpcClient<HttpTransport>) {
-    let arc_deoxys = Arc::new(deoxys);
-    let arc_pathfinder = Arc::new(pathfinder);
-    let parallels_queries = 10;
-    let mut diff = false;
-
-    for block_group in (0..=100_000).step_by(parallels_queries) {
-        let mut set = tokio::task::JoinSet::new();
-        for offset in 0..parallels_queries {
-            let block_id = (block_group + offset) as u64;
-            let block = BlockId::Number(block_id);
-            let clone_deoxys = Arc::clone(&arc_deoxys);
-            let clone_pathfinder = Arc::clone(&arc_pathfinder);
-            set.spawn(async move {
-                let response_deoxys = clone_deoxys
-                    .get_block_transaction_count(block)
-                    .await
-                    .expect("Error waiting for response from Deoxys node");
-
-                let response_pathfinder = clone_pathfinder.get_block_transaction_count(block).await;
-
-                match response_pathfinder {
-                    Ok(response_pathfinder) => {
-                        if response_deoxys != response_pathfinder {
-                            Err(format!("block {}", block_id))
-                        } else {
-                            Ok(())
-                        }
-                    }
-                    Err(e) => Err(format!("Error pathfinder: {}", e)),
-                }
-            });
-        }
-        while let Some(result) = set.join_next().await {
-            match result {
-                Ok(result) => match result {
-                    Ok(_) => {}
-                    Err(e) => {
-                        println!("{}", e);
-                        diff = true;
-                    }
-                },
-                Err(e) => {
-                    panic!("{}", e);
-                }
-            }
-        }
+async fn work_loop(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let options = SweepOptions {
+        components: vec![Component::Block],
+        ..Default::default()
+    };
+    let report = sweep(&deoxys, &pathfinder, 0..=100_000, &options)
+        .await
+        .expect("Error while sweeping blocks");
+
+    println!("{report}");
+    assert!(report.divergences.is_empty());
+}
+
+#[require(spec_version = "0.5.1")]
+#[rstest]
+#[case::uniform(Strategy::Uniform)]
+#[case::recent_tip(Strategy::RecentTip { depth: 1000 })]
+#[tokio::test]
+#[logging]
+async fn work_with_sampled_blocks(
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
+    mut sampler: Sampler,
+    #[case] strategy: Strategy,
+) {
+    let deoxys = &clients[DEOXYS];
+    let pathfinder = &clients[PATHFINDER];
+
+    let blocks = sampler
+        .sample(deoxys, strategy, 5)
+        .await
+        .expect("Error waiting for response from Deoxys node");
+
+    for block_number in blocks {
+        let block_id = BlockId::Number(block_number);
+
+        let response_deoxys = deoxys
+            .get_block_transaction_count(block_id)
+            .await
+            .expect("Error waiting for response from Deoxys node");
+        let response_pathfinder = pathfinder
+            .get_block_transaction_count(block_id)
+            .await
+            .expect("Error waiting for response from Pathfinder node");
+
+        assert_eq!(response_deoxys, response_pathfinder, "block {block_number}");
     }
-    assert_eq!(diff, false);
 }
@@ -8,11 +8,11 @@ use std::sync::Arc;
 use std::{assert_matches::assert_matches, collections::HashMap};
 
 use starknet_core::types::{BlockId, BlockTag, FieldElement, StarknetError};
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
+use unit_tests::{
+    constants::DEOXYS,
+    sampling::{Sampler, Strategy},
 };
-use unit_tests::constants::DEOXYS;
 
 ///
 /// Unit test for `starknet_get_block_with_tx_hashes`
@@ -23,7 +23,7 @@ use unit_tests::constants::DEOXYS;
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -46,7 +46,7 @@ async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -87,7 +87,7 @@ async fn work_existing_block(clients: HashMap<String, JsonRpcClient<HttpTranspor
 #[rstest]
 #[tokio::test]
 #[ignore = "Pending fails some times when called on the cusp of being accepted, need virtual sequencer"]
-async fn work_pending_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_pending_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -114,8 +114,8 @@ async fn work_pending_block(clients: HashMap<String, JsonRpcClient<HttpTransport
 }
 
 async fn work_with_block(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
     block_number: u64,
 ) {
     let block_number = BlockId::Number(block_number);
@@ -138,8 +138,8 @@ async fn work_with_block(
 #[rstest]
 #[tokio::test]
 async fn work_with_block_1(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_block(deoxys, pathfinder, 1).await;
 }
@@ -149,8 +149,8 @@ async fn work_with_block_1(
 #[rstest]
 #[tokio::test]
 async fn work_with_block_3800(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_block(deoxys, pathfinder, 1).await;
 }
@@ -160,8 +160,8 @@ async fn work_with_block_3800(
 #[rstest]
 #[tokio::test]
 async fn work_with_block_5066(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_block(deoxys, pathfinder, 1).await;
 }
@@ -171,17 +171,55 @@ async fn work_with_block_5066(
 #[rstest]
 #[tokio::test]
 async fn work_with_block_1500(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_block(deoxys, pathfinder, 1500).await;
 }
 
+#[require(spec_version = "0.5.1")]
+#[rstest]
+#[case::uniform(Strategy::Uniform)]
+#[case::recent_tip(Strategy::RecentTip { depth: 1000 })]
+#[tokio::test]
+#[logging]
+async fn work_with_sampled_blocks(
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
+    mut sampler: Sampler,
+    #[case] strategy: Strategy,
+) {
+    let deoxys = &clients[DEOXYS];
+    let pathfinder = &clients[PATHFINDER];
+
+    let blocks = sampler
+        .sample(deoxys, strategy, 5)
+        .await
+        .expect("Error waiting for response from Deoxys node");
+
+    for block_number in blocks {
+        let block_id = BlockId::Number(block_number);
+
+        let response_deoxys = deoxys
+            .get_block_with_tx_hashes(block_id)
+            .await
+            .expect("Error waiting for response from Deoxys node");
+        let response_pathfinder = pathfinder
+            .get_block_with_tx_hashes(block_id)
+            .await
+            .expect("Error waiting for response from Pathfinder node");
+
+        assert_eq!(response_deoxys, response_pathfinder, "block {block_number}");
+    }
+}
+
 #[require(block_min = 100_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
 #[ignore = "ignore this test"]
-async fn work_loop(deoxys: JsonRpcClient<HttpTransport>, pathfinder: JsonRpcClient<HttpTransport>) {
+async fn work_loop(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
     let arc_deoxys = Arc::new(deoxys);
     let arc_pathfinder = Arc::new(pathfinder);
     let parallels_queries = 10;
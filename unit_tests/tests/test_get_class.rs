@@ -4,14 +4,14 @@ mod common;
 use common::*;
 
 use starknet_core::types::{BlockId, FieldElement, StarknetError};
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 use std::assert_matches::assert_matches;
 use std::collections::HashMap;
 
 #[rstest]
 #[tokio::test]
 #[ignore = "Fix failing unwrap due to empty constant"]
-async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let test_contract_class_hash =
@@ -28,7 +28,7 @@ async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[rstest]
 #[tokio::test]
 #[ignore = "Fix failing unwrap due to empty constant"]
-async fn fail_non_existing_class_hash(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_class_hash(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let unknown_contract_class_hash =
@@ -48,7 +48,7 @@ async fn fail_non_existing_class_hash(clients: HashMap<String, JsonRpcClient<Htt
 #[tokio::test]
 #[ignore = "Fix failing unwrap due to empty constant"]
 async fn work_ok_retrieving_class_for_contract_version_0(
-    clients: HashMap<String, JsonRpcClient<HttpTransport>>,
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
 ) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
@@ -74,7 +74,7 @@ async fn work_ok_retrieving_class_for_contract_version_0(
 #[tokio::test]
 #[ignore = "Fix failing unwrap due to empty constant"]
 async fn work_ok_retrieving_class_for_contract_version_1(
-    clients: HashMap<String, JsonRpcClient<HttpTransport>>,
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
 ) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
@@ -8,7 +8,7 @@ use flate2::read::GzDecoder;
 use starknet_core::types::{
     contract::legacy::LegacyProgram, BlockId, BlockTag, ContractClass, FieldElement, StarknetError,
 };
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 
 ///
 /// unit test for `starknet_get_class_at`
@@ -19,7 +19,7 @@ use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, Provid
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -45,7 +45,7 @@ async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_contract(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_contract(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -71,7 +71,7 @@ async fn fail_non_existing_contract(clients: HashMap<String, JsonRpcClient<HttpT
 #[rstest]
 #[tokio::test]
 async fn work_contract_v0(
-    clients: HashMap<String, JsonRpcClient<HttpTransport>>,
+    clients: HashMap<String, JsonRpcClient<DittoTransport>>,
 ) -> anyhow::Result<()> {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
@@ -129,8 +129,8 @@ async fn work_contract_v0(
 #[rstest]
 #[tokio::test]
 async fn work_contract_v1(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     let response_deoxys = deoxys
         .get_class_at(
@@ -6,9 +6,10 @@ use std::{assert_matches::assert_matches, sync::Arc};
 use anyhow::anyhow;
 use common::*;
 use starknet::macros::{felt_hex, selector};
-use starknet_core::types::{BlockId, EventFilter, EventsPage, FieldElement, StarknetError};
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_core::types::{EventsPage, FieldElement, StarknetError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 use tokio::task::JoinSet;
+use unit_tests::events::{get_events, EventQuery};
 
 ///
 /// Test for RPC call `starknet_getEvents`.
@@ -35,14 +36,13 @@ use tokio::task::JoinSet;
 #[rstest]
 #[tokio::test]
 #[logging]
-async fn fail_invalid_block_number(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_invalid_block_number(deoxys: JsonRpcClient<DittoTransport>) {
     let keys: Vec<Vec<FieldElement>> = vec![vec![selector!("transaction_executed")]];
     let block_nu: u64 = u64::MAX;
     let block_range: u64 = 100;
+    let query = EventQuery::block(block_nu, &keys, block_range);
 
-    let response_deoxys = get_events(&deoxys, &keys, block_nu, block_range)
-        .await
-        .err();
+    let response_deoxys = get_events(&deoxys, &query, None).await.err();
 
     assert_matches!(
         response_deoxys,
@@ -62,14 +62,13 @@ async fn fail_invalid_block_number(deoxys: JsonRpcClient<HttpTransport>) {
 #[rstest]
 #[tokio::test]
 #[logging]
-async fn fail_invalid_keys(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_invalid_keys(deoxys: JsonRpcClient<DittoTransport>) {
     let keys: Vec<Vec<FieldElement>> = vec![vec![selector!("")]];
     let block_nu: u64 = 50000;
     let block_range: u64 = 100;
+    let query = EventQuery::block(block_nu, &keys, block_range);
 
-    let response_deoxys = get_events(&deoxys, &keys, block_nu, block_range)
-        .await
-        .expect(ERR_DEOXYS);
+    let response_deoxys = get_events(&deoxys, &query, None).await.expect(ERR_DEOXYS);
 
     log::info!(
         "Events at block {block_nu}: {}",
@@ -88,14 +87,13 @@ async fn fail_invalid_keys(deoxys: JsonRpcClient<HttpTransport>) {
 #[rstest]
 #[tokio::test]
 #[logging]
-async fn fail_invalid_block_range(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_invalid_block_range(deoxys: JsonRpcClient<DittoTransport>) {
     let keys: Vec<Vec<FieldElement>> = vec![vec![selector!("")]];
     let block_nu: u64 = 50000;
     let block_range: u64 = 0;
+    let query = EventQuery::block(block_nu, &keys, block_range);
 
-    let response_deoxys = get_events(&deoxys, &keys, block_nu, block_range)
-        .await
-        .err();
+    let response_deoxys = get_events(&deoxys, &query, None).await.err();
 
     // for some reason a block range of 0 results in an internal error
     assert_matches!(
@@ -117,19 +115,18 @@ async fn fail_invalid_block_range(deoxys: JsonRpcClient<HttpTransport>) {
 #[tokio::test]
 #[logging]
 async fn work_valid_call_no_selector(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     let keys: Vec<Vec<FieldElement>> = vec![vec![selector!("transaction_executed")]];
     let block_nu: u64 = 50000;
     let block_hash: FieldElement =
         felt_hex!("0x053315a56543737cd1b2dc40c60e84d03a9b10d712c9b29f488dc979f0cd56bd");
     let block_range: u64 = 100;
+    let query = EventQuery::block(block_nu, &keys, block_range);
 
-    let response_deoxys = get_events(&deoxys, &keys, block_nu, block_range)
-        .await
-        .expect(ERR_DEOXYS);
-    let response_pathfinder = get_events(&pathfinder, &keys, block_nu, block_range)
+    let response_deoxys = get_events(&deoxys, &query, None).await.expect(ERR_DEOXYS);
+    let response_pathfinder = get_events(&pathfinder, &query, None)
         .await
         .expect(ERR_PATHFINDER);
 
@@ -153,8 +150,8 @@ async fn work_valid_call_no_selector(
 #[tokio::test]
 #[logging]
 async fn work_valid_call_single_selector(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     // event type to retrieve
     let keys: Vec<Vec<FieldElement>> = vec![vec![selector!("transaction_executed")]];
@@ -162,11 +159,10 @@ async fn work_valid_call_single_selector(
     let block_hash: FieldElement =
         felt_hex!("0x053315a56543737cd1b2dc40c60e84d03a9b10d712c9b29f488dc979f0cd56bd");
     let block_range: u64 = 100;
+    let query = EventQuery::block(block_nu, &keys, block_range);
 
-    let response_deoxys = get_events(&deoxys, &keys, block_nu, block_range)
-        .await
-        .expect(ERR_DEOXYS);
-    let response_pathfinder = get_events(&pathfinder, &keys, block_nu, block_range)
+    let response_deoxys = get_events(&deoxys, &query, None).await.expect(ERR_DEOXYS);
+    let response_pathfinder = get_events(&pathfinder, &query, None)
         .await
         .expect(ERR_PATHFINDER);
 
@@ -190,8 +186,8 @@ async fn work_valid_call_single_selector(
 #[tokio::test]
 #[logging]
 async fn work_valid_call_multiple_selector(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     let keys: Vec<Vec<FieldElement>> = vec![vec![
         selector!("transaction_executed"),
@@ -201,11 +197,10 @@ async fn work_valid_call_multiple_selector(
     let block_hash: FieldElement =
         felt_hex!("0x053315a56543737cd1b2dc40c60e84d03a9b10d712c9b29f488dc979f0cd56bd");
     let block_range: u64 = 100;
+    let query = EventQuery::block(block_nu, &keys, block_range);
 
-    let response_deoxys = get_events(&deoxys, &keys, block_nu, block_range)
-        .await
-        .expect(ERR_DEOXYS);
-    let response_pathfinder = get_events(&pathfinder, &keys, block_nu, block_range)
+    let response_deoxys = get_events(&deoxys, &query, None).await.expect(ERR_DEOXYS);
+    let response_pathfinder = get_events(&pathfinder, &query, None)
         .await
         .expect(ERR_PATHFINDER);
 
@@ -217,37 +212,8 @@ async fn work_valid_call_multiple_selector(
     deep_check_events(deoxys, response_deoxys, keys, block_hash, block_nu).await;
 }
 
-async fn get_events(
-    client: &JsonRpcClient<HttpTransport>,
-    keys: &[Vec<FieldElement>],
-    block_nu: u64,
-    block_range: u64,
-) -> Result<EventsPage, ProviderError> {
-    client
-        .get_events(
-            EventFilter {
-                // getEvents is a applied through a filter
-                // this filter consists of a block range...
-                from_block: Some(BlockId::Number(block_nu)),
-                to_block: Some(BlockId::Number(block_nu)),
-                // a beginning contract address...
-                address: None,
-                // and keys used to filter out events. Keys can include a hash of the event
-                // and even event return values for further filtering
-                keys: Some(keys.to_vec()),
-            },
-            // in cases were a first search does not yield enough results, a continuation key
-            // can be used to keep searching from the point of the last getEvent search
-            None,
-            // chunk size marks the number of events to look through and filter
-            // this means that there cannot be more than chunk_size events returned by getEvents
-            block_range,
-        )
-        .await
-}
-
 async fn deep_check_events(
-    deoxys: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
     response_deoxys: EventsPage,
     keys: Vec<Vec<FieldElement>>,
     block_hash: FieldElement,
@@ -3,10 +3,11 @@
 mod common;
 use common::*;
 
-use starknet_core::types::{BlockId, BlockTag, StarknetError};
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_core::types::{BlockId, StarknetError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 use std::assert_matches::assert_matches;
 use std::collections::HashMap;
+use unit_tests::tip::{latest, TipOptions};
 
 /// Test for the `get_state_update` Deoxys RPC method
 /// # Arguments
@@ -24,7 +25,7 @@ use std::collections::HashMap;
 #[rstest]
 #[tokio::test]
 #[ignore = "Need to fix unwrap on error due to empty constants"]
-async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     assert_matches!(
@@ -37,18 +38,21 @@ async fn fail_non_existing_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[rstest]
 #[tokio::test]
 #[ignore = "Need to fix unwrap on error due to empty constants"]
-async fn work_existing_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
-    let deoxys = &clients[DEOXYS];
-    let pathfinder = &clients[PATHFINDER];
-
-    let response_deoxys = deoxys
-        .get_state_update(BlockId::Tag(BlockTag::Latest))
-        .await
-        .expect("Deoxys : Error while getting the state update");
-    let response_pathfinder = pathfinder
-        .get_state_update(BlockId::Tag(BlockTag::Latest))
-        .await
-        .expect("RPC : Error while getting the state update");
-
-    assert_eq!(response_deoxys, response_pathfinder);
+async fn work_existing_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
+    let (tip, responses) = latest(&clients, &TipOptions::default(), |client, block_id| {
+        client.get_state_update(block_id)
+    })
+    .await
+    .expect("Error while getting the state update");
+
+    let block_id = BlockId::Hash(tip.block_hash);
+    for (name, client) in clients.iter() {
+        let response = client
+            .get_state_update(block_id)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(responses[name], response, "{name}");
+    }
+
+    assert_eq!(responses[DEOXYS], responses[PATHFINDER]);
 }
@@ -5,7 +5,7 @@ use std::{assert_matches::assert_matches, collections::HashMap};
 
 use common::*;
 use starknet_core::types::{BlockId, FieldElement, StarknetError};
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 
 ///
 /// Unit test for `starknet_getTransactionByBlockIdAndIndex`
@@ -16,7 +16,7 @@ use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, Provid
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existent_block(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existent_block(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -39,7 +39,7 @@ async fn fail_non_existent_block(clients: HashMap<String, JsonRpcClient<HttpTran
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existent_block_index(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existent_block_index(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -64,7 +64,7 @@ async fn fail_non_existent_block_index(clients: HashMap<String, JsonRpcClient<Ht
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_deploy_invoke(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_deploy_invoke(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -104,7 +104,7 @@ async fn work_deploy_invoke(clients: HashMap<String, JsonRpcClient<HttpTransport
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_deploy_l1_handler(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_deploy_l1_handler(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -144,7 +144,7 @@ async fn work_deploy_l1_handler(clients: HashMap<String, JsonRpcClient<HttpTrans
 #[require(block_min = 49_990, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_deploy_declare(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_deploy_declare(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -184,7 +184,7 @@ async fn work_deploy_declare(clients: HashMap<String, JsonRpcClient<HttpTranspor
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_deploy_account(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_deploy_account(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
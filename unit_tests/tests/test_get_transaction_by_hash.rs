@@ -5,7 +5,7 @@ use std::{assert_matches::assert_matches, collections::HashMap};
 
 use common::*;
 use starknet_core::types::{FieldElement, StarknetError, Transaction};
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 
 ///
 /// Unit test for `starknet_getTransactionByHash`
@@ -16,7 +16,7 @@ use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, Provid
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_transaction(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_non_existing_transaction(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -41,7 +41,7 @@ async fn fail_non_existing_transaction(clients: HashMap<String, JsonRpcClient<Ht
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_transaction_invoke(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_transaction_invoke(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -68,7 +68,7 @@ async fn work_transaction_invoke(clients: HashMap<String, JsonRpcClient<HttpTran
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_transaction_l1_handler(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_transaction_l1_handler(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -95,7 +95,7 @@ async fn work_transaction_l1_handler(clients: HashMap<String, JsonRpcClient<Http
 #[require(block_min = 49_990spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_transaction_declare(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_transaction_declare(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -124,7 +124,7 @@ async fn work_transaction_declare(clients: HashMap<String, JsonRpcClient<HttpTra
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_transaction_deploy_account(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_transaction_deploy_account(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -144,8 +144,8 @@ async fn work_transaction_deploy_account(clients: HashMap<String, JsonRpcClient<
 
 /// helper function for testing transaction by hash
 async fn work_with_hash(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
     transaction_hash: &str,
 ) {
     let transaction_hash =
@@ -169,8 +169,8 @@ async fn work_with_hash(
 #[rstest]
 #[tokio::test]
 async fn work_with_first_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -185,8 +185,8 @@ async fn work_with_first_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_deploy_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -201,8 +201,8 @@ async fn work_with_deploy_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_invoke_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -217,8 +217,8 @@ async fn work_with_invoke_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_deploy_transaction_block_1(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -233,8 +233,8 @@ async fn work_with_deploy_transaction_block_1(
 #[rstest]
 #[tokio::test]
 async fn work_with_invoke_transaction_block_10(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -249,8 +249,8 @@ async fn work_with_invoke_transaction_block_10(
 #[rstest]
 #[tokio::test]
 async fn work_with_deploy_transaction_block_10(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
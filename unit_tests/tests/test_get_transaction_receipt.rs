@@ -6,16 +6,13 @@ use common::*;
 use std::{assert_matches::assert_matches, collections::HashMap};
 
 use starknet_core::types::{FieldElement, StarknetError};
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
-};
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
 
 // invalid transaction_hash
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_invalid_transaction_hash(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_invalid_transaction_hash(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -32,8 +29,8 @@ async fn fail_invalid_transaction_hash(clients: HashMap<String, JsonRpcClient<Ht
 }
 
 async fn work_with_hash(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
     transaction_hash: &str,
 ) {
     let transaction_hash =
@@ -57,8 +54,8 @@ async fn work_with_hash(
 #[rstest]
 #[tokio::test]
 async fn work_with_reverted_transaction_block_200_000(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -73,8 +70,8 @@ async fn work_with_reverted_transaction_block_200_000(
 #[rstest]
 #[tokio::test]
 async fn work_with_first_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -89,8 +86,8 @@ async fn work_with_first_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_deploy_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -105,8 +102,8 @@ async fn work_with_deploy_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_invoke_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -121,8 +118,8 @@ async fn work_with_invoke_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_deploy_transaction_block_1(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -7,7 +7,7 @@ use common::*;
 use starknet_core::types::{
     BlockId, BlockTag, FieldElement, StarknetError, TransactionExecutionStatus, TransactionStatus,
 };
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 
 ///
 /// Unit test for `starknet_getTransactionStatus`
@@ -18,7 +18,7 @@ use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, Provid
 #[require(spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn fail_invalid_transaction(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn fail_invalid_transaction(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
 
     let response_deoxys = deoxys
@@ -43,7 +43,7 @@ async fn fail_invalid_transaction(clients: HashMap<String, JsonRpcClient<HttpTra
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_transaction_accepted_on_l1(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_transaction_accepted_on_l1(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -70,7 +70,7 @@ async fn work_transaction_accepted_on_l1(clients: HashMap<String, JsonRpcClient<
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_transaction_accepted_on_l2(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_transaction_accepted_on_l2(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -112,7 +112,7 @@ async fn work_transaction_accepted_on_l2(clients: HashMap<String, JsonRpcClient<
 #[require(block_min = 50_000, spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn work_transaction_reverted(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn work_transaction_reverted(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -134,8 +134,8 @@ async fn work_transaction_reverted(clients: HashMap<String, JsonRpcClient<HttpTr
 }
 
 async fn work_with_hash(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
     transaction_hash: &str,
 ) {
     let tx = FieldElement::from_hex_be(transaction_hash).unwrap();
@@ -154,8 +154,8 @@ async fn work_with_hash(
 #[rstest]
 #[tokio::test]
 async fn work_with_first_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -170,8 +170,8 @@ async fn work_with_first_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_deploy_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -186,8 +186,8 @@ async fn work_with_deploy_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_invoke_transaction_block_0(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
@@ -202,8 +202,8 @@ async fn work_with_invoke_transaction_block_0(
 #[rstest]
 #[tokio::test]
 async fn work_with_deploy_transaction_block_1(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     work_with_hash(
         deoxys,
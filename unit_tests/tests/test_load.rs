@@ -0,0 +1,99 @@
+mod common;
+use common::*;
+
+use std::time::Duration;
+
+use starknet_providers::JsonRpcClient;
+use unit_tests::bench::{
+    load::{run_load, saturation, LoadOptions, Mix, StepResult},
+    Method, Stats, Targets,
+};
+
+fn step(target_rate: f64, requests: u64, latency: Duration) -> StepResult {
+    let mut stats = Stats::new();
+    for _ in 0..requests {
+        stats.record(latency);
+    }
+    stats.elapsed = Duration::from_secs(1);
+
+    StepResult {
+        target_rate,
+        latency: stats.clone(),
+        service: stats,
+    }
+}
+
+///
+/// Load generator options
+///
+/// purpose: method mixes and rate ramps are parsed and generated as configured.
+/// success case: mixes round trip through their string form, ramps include both ends.
+///
+#[rstest]
+fn work_options() {
+    let mix = "get_storage_at=60,starknet_call=20,get_events=20"
+        .parse::<Mix>()
+        .unwrap();
+    assert_eq!(mix, Mix::default());
+    assert_eq!(mix.to_string().parse::<Mix>().unwrap(), mix);
+    assert!("get_storage_at".parse::<Mix>().is_err());
+    assert!("get_storage_at=a".parse::<Mix>().is_err());
+
+    let options = LoadOptions::ramp(10.0, 50.0, 20.0, Duration::from_secs(1));
+    assert_eq!(options.rates, vec![10.0, 30.0, 50.0]);
+}
+
+///
+/// Saturation detection
+///
+/// purpose: find the rate at which a node stops keeping up.
+/// success case: the first step whose throughput falls behind or whose latency explodes.
+///
+#[rstest]
+fn work_saturation() {
+    let ms = Duration::from_millis;
+
+    let steps = [step(10.0, 10, ms(5)), step(20.0, 20, ms(6))];
+    assert_eq!(saturation(&steps), None);
+
+    let steps = [step(10.0, 10, ms(5)), step(20.0, 15, ms(6))];
+    assert_eq!(saturation(&steps), Some(20.0));
+
+    let steps = [step(10.0, 10, ms(5)), step(20.0, 20, ms(500))];
+    assert_eq!(saturation(&steps), Some(20.0));
+}
+
+///
+/// Open-loop load
+///
+/// purpose: requests are issued at the target rate regardless of responses.
+/// success case: each node receives rate * duration requests.
+///
+#[require(block_min = 50_000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_fixed_rate(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let targets = Targets::fetch(&pathfinder, vec![50_000])
+        .await
+        .expect(ERR_PATHFINDER);
+    let options = LoadOptions {
+        mix: Mix(vec![(Method::GetStorageAt, 1.0)]),
+        ..LoadOptions::fixed(5.0, Duration::from_secs(2))
+    };
+
+    let report = run_load(
+        &[(DEOXYS, &deoxys), (PATHFINDER, &pathfinder)],
+        &targets,
+        &options,
+    )
+    .await
+    .expect("Error while generating load");
+    println!("{report}");
+
+    for node in [DEOXYS, PATHFINDER] {
+        assert_eq!(report.steps[node][0].latency.count(), 10);
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+use common::*;
+
+use starknet_providers::{JsonRpcClient, Provider};
+use unit_tests::{
+    monitor::{Alert, Monitor, MonitorOptions},
+    sweep::{Component, Divergence},
+};
+
+///
+/// Monitor alerts
+///
+/// purpose: alerts are forwarded as flat json objects tagged with their kind.
+/// success case: alerts serialize with an `alert` field and deserialize back.
+///
+#[rstest]
+fn work_alert_json() {
+    let alerts = [
+        Alert::Divergence(Divergence {
+            block_number: 1,
+            component: Component::StateUpdate,
+            message: String::from("getStateUpdate: /state_diff/nonces/0 0x1 != 0x2"),
+        }),
+        Alert::SyncLag {
+            node: String::from(DEOXYS),
+            tip: 10,
+            reference_tip: 20,
+            lag: 10,
+        },
+        Alert::SyncRecovered {
+            node: String::from(DEOXYS),
+            lag: 0,
+        },
+    ];
+
+    let json = serde_json::to_value(&alerts[1]).unwrap();
+    assert_eq!(json["alert"], "sync_lag");
+    assert_eq!(json["lag"], 10);
+
+    for alert in alerts {
+        let json = serde_json::to_string(&alert).unwrap();
+        assert_eq!(serde_json::from_str::<Alert>(&json).unwrap(), alert);
+    }
+}
+
+///
+/// Monitor polling
+///
+/// purpose: the monitor compares every block both nodes have since it started.
+/// success case: recent blocks do not diverge and the monitor moves past them.
+///
+#[require(block_min = "latest", spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_poll(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let tip = deoxys.block_number().await.expect(ERR_DEOXYS);
+    let options = MonitorOptions {
+        lag_threshold: u64::MAX,
+        ..Default::default()
+    };
+    let mut monitor = Monitor::new(options, tip.saturating_sub(2));
+
+    let alerts = monitor
+        .poll(&deoxys, &pathfinder)
+        .await
+        .expect("Error while polling nodes");
+
+    for alert in alerts.iter() {
+        println!("{alert}");
+    }
+    assert!(alerts.is_empty());
+    assert!(monitor.next_block() > tip.saturating_sub(2));
+}
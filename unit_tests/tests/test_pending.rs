@@ -0,0 +1,202 @@
+mod common;
+use common::*;
+
+use serde_json::{json, Value};
+use starknet_core::types::{FieldElement, PendingBlockWithTxHashes};
+use starknet_providers::JsonRpcClient;
+use unit_tests::{
+    batch::Request,
+    cassette::Cassette,
+    pending::{compare_pending, shared_transactions, PendingComparison},
+    replay::CapturedCall,
+    transport::{RawError, RawResponse},
+};
+
+/// Starknet error code of a block which does not exist
+const BLOCK_NOT_FOUND: i64 = 24;
+
+fn pending_block(parent_hash: &str, transactions: &[&str]) -> Value {
+    json!({
+        "transactions": transactions,
+        "timestamp": 1700000000,
+        "sequencer_address": "0x1",
+        "parent_hash": parent_hash,
+        "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+        "starknet_version": "0.12.3"
+    })
+}
+
+fn block(block_hash: &str, block_number: u64) -> Value {
+    json!({
+        "status": "ACCEPTED_ON_L2",
+        "block_hash": block_hash,
+        "parent_hash": "0x1",
+        "block_number": block_number,
+        "new_root": "0x1",
+        "timestamp": 1700000000,
+        "sequencer_address": "0x1",
+        "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+        "starknet_version": "0.12.3",
+        "transactions": []
+    })
+}
+
+fn call(method: &str, params: Value, response: RawResponse) -> CapturedCall {
+    CapturedCall {
+        timestamp_ms: 0,
+        request: Request::new(method, params),
+        response: Some(response),
+    }
+}
+
+///
+/// Node whose pending block is built on `parent_hash`, with a latest block `latest` of
+/// hash `latest_hash`. Other blocks are unknown to it.
+///
+fn node(
+    parent_hash: &str,
+    transactions: &[&str],
+    latest_hash: &str,
+    latest: u64,
+    unknown_hash: &str,
+) -> JsonRpcClient<DittoTransport> {
+    let get_block = "starknet_getBlockWithTxHashes";
+    let calls = vec![
+        call(
+            get_block,
+            json!({ "block_id": "pending" }),
+            RawResponse::Success {
+                result: pending_block(parent_hash, transactions),
+            },
+        ),
+        call(
+            "starknet_blockNumber",
+            json!([]),
+            RawResponse::Success {
+                result: json!(latest),
+            },
+        ),
+        call(
+            get_block,
+            json!({ "block_id": { "block_hash": latest_hash } }),
+            RawResponse::Success {
+                result: block(latest_hash, latest),
+            },
+        ),
+        call(
+            get_block,
+            json!({ "block_id": { "block_hash": unknown_hash } }),
+            RawResponse::Error {
+                error: RawError {
+                    code: BLOCK_NOT_FOUND,
+                    message: String::from("Block not found"),
+                    data: None,
+                },
+            },
+        ),
+    ];
+
+    JsonRpcClient::new(DittoTransport::replay(Cassette::new(calls)))
+}
+
+///
+/// Comparison of pending blocks
+///
+/// purpose: measure the overlap of two pending blocks.
+/// success case: shared transactions are found whatever their order, overlap is shared
+///               over all transactions.
+///
+#[rstest]
+fn work_pending_overlap() {
+    let pending = |transactions: &[&str]| -> PendingBlockWithTxHashes {
+        serde_json::from_value(pending_block("0xa", transactions)).unwrap()
+    };
+    let felt = |n: u64| FieldElement::from(n);
+
+    let shared = shared_transactions(
+        &pending(&["0x1", "0x2", "0x3"]),
+        &pending(&["0x3", "0x2", "0x4"]),
+    );
+    assert_eq!(shared, vec![felt(2), felt(3)]);
+
+    let comparison = PendingComparison {
+        deoxys_transactions: 3,
+        pathfinder_transactions: 3,
+        shared_transactions: shared.len(),
+        receipt_mismatches: vec![],
+        parents: vec![],
+    };
+    assert_eq!(comparison.overlap(), 0.5);
+
+    let empty = PendingComparison {
+        deoxys_transactions: 0,
+        pathfinder_transactions: 0,
+        shared_transactions: 0,
+        ..comparison
+    };
+    assert_eq!(empty.overlap(), 1.0);
+}
+
+///
+/// Comparison of pending blocks
+///
+/// purpose: locate the parent of each pending block on the other node.
+/// success case: a parent which is the other node's latest block is reported as such, a
+///               parent unknown to the other node is reported as unknown.
+///
+#[rstest]
+#[tokio::test]
+async fn work_pending_parents() {
+    // Deoxys builds on Pathfinder's latest block 0xa, Pathfinder on a block 0xb which
+    // Deoxys does not have
+    let deoxys = node("0xa", &["0x1", "0x2"], "0xc", 9, "0xb");
+    let pathfinder = node("0xb", &["0x3"], "0xa", 10, "0xd");
+
+    let comparison = compare_pending(&deoxys, &pathfinder)
+        .await
+        .expect("Error while comparing pending blocks");
+    println!("{comparison}");
+
+    assert_eq!(comparison.deoxys_transactions, 2);
+    assert_eq!(comparison.pathfinder_transactions, 1);
+    assert_eq!(comparison.overlap(), 0.0);
+
+    let [parent_deoxys, parent_pathfinder] = &comparison.parents[..] else {
+        panic!("Expected a parent check for each node");
+    };
+    assert_eq!(parent_deoxys.node, DEOXYS);
+    assert_eq!(parent_deoxys.parent_number, Some(10));
+    assert!(parent_deoxys.is_other_latest());
+
+    assert_eq!(parent_pathfinder.node, PATHFINDER);
+    assert_eq!(parent_pathfinder.other_latest, 9);
+    assert_eq!(parent_pathfinder.parent_number, None);
+    assert!(!parent_pathfinder.is_other_latest());
+}
+
+///
+/// Comparison of pending blocks
+///
+/// purpose: pending blocks of both nodes may hold different transactions, but must agree
+///          on the transactions they share and be built on top of the same block.
+/// success case: shared transactions have the same receipts, and each pending block's
+///               parent is the other node's latest block.
+///
+#[ignore = "Pending fails some times when called on the cusp of being accepted, need virtual sequencer"]
+#[require(block_min = "latest", spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_with_pending(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let comparison = compare_pending(&deoxys, &pathfinder)
+        .await
+        .expect("Error while comparing pending blocks");
+
+    println!("{comparison}");
+    assert!(comparison.receipt_mismatches.is_empty());
+    for parent in comparison.parents.iter() {
+        assert!(parent.is_other_latest(), "{parent}");
+    }
+}
@@ -0,0 +1,110 @@
+mod common;
+use common::*;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use serde_json::json;
+use starknet_core::types::{FieldElement, StateDiff};
+use starknet_providers::JsonRpcClient;
+use unit_tests::{
+    cassette::Cassette,
+    drilldown::StateEntry,
+    reconstruct::{reconstruct_and_check, ReconstructedState},
+};
+
+/// Temporary file, removed when dropped so that failed assertions clean up as well
+struct TempFile(PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn storage_diff(value: &str) -> StateDiff {
+    serde_json::from_value(json!({
+        "storage_diffs": [{
+            "address": "0x1",
+            "storage_entries": [{ "key": "0x2", "value": value }]
+        }],
+        "deprecated_declared_classes": [],
+        "declared_classes": [],
+        "deployed_contracts": [{ "address": "0x1", "class_hash": "0x3" }],
+        "replaced_classes": [],
+        "nonces": []
+    }))
+    .unwrap()
+}
+
+///
+/// State reconstruction from state updates
+///
+/// purpose: apply state diffs in order and save the resulting state.
+/// success case: later writes override earlier ones and the state survives a round trip to disk.
+///
+#[rstest]
+fn work_apply() {
+    let entry = StateEntry::Storage {
+        contract: FieldElement::ONE,
+        key: FieldElement::TWO,
+    };
+
+    let mut state = ReconstructedState::new();
+    state.apply(&storage_diff("0x4"));
+    state.apply(&storage_diff("0x5"));
+
+    assert_eq!(state.next_block, 2);
+    assert_eq!(state.len(), 2);
+    assert_eq!(state.get(&entry), Some(FieldElement::from(5u64)));
+
+    let file =
+        TempFile(std::env::temp_dir().join(format!("ditto-state-{}.json", std::process::id())));
+    state.save(&file.0).unwrap();
+    let loaded = ReconstructedState::load(&file.0).unwrap();
+
+    assert_eq!(loaded.next_block, 2);
+    assert_eq!(loaded.get(&entry), Some(FieldElement::from(5u64)));
+}
+
+///
+/// State reconstruction from state updates
+///
+/// purpose: check historical point queries against the state rebuilt from state updates.
+/// success case: `getStorageAt`, `getNonce` and `getClassHashAt` agree with the rebuilt state.
+///
+#[require(block_min = 1000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_with_blocks(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
+    for (name, client) in clients.iter() {
+        let mut state = ReconstructedState::new();
+        let mismatches = reconstruct_and_check(client, &mut state, &[10, 100, 1000], 50, 0)
+            .await
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        for mismatch in mismatches.iter() {
+            println!("{name}: {mismatch}");
+        }
+        assert!(mismatches.is_empty());
+    }
+}
+
+///
+/// State reconstruction from state updates
+///
+/// purpose: check a block which is before a saved state.
+/// fail case: the block can no longer be rebuilt, no request is sent.
+///
+#[rstest]
+#[tokio::test]
+async fn fail_block_before_state() {
+    let client = JsonRpcClient::new(DittoTransport::replay(Cassette::new(vec![])));
+    let mut state = ReconstructedState::new();
+    state.apply(&storage_diff("0x4"));
+    state.apply(&storage_diff("0x5"));
+
+    let result = reconstruct_and_check(&client, &mut state, &[1, 5], 0, 0).await;
+
+    assert!(result.is_err());
+    assert_eq!(state.next_block, 2);
+}
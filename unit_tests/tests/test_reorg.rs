@@ -0,0 +1,111 @@
+mod common;
+use common::*;
+
+use std::{
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+use starknet_core::types::FieldElement;
+use unit_tests::reorg::ReorgTracker;
+
+/// Blocks of a fake chain, whose hashes differ on each fork
+fn chain(blocks: RangeInclusive<u64>, fork: u64) -> Vec<(u64, FieldElement)> {
+    blocks
+        .map(|block_number| {
+            (
+                block_number,
+                FieldElement::from(fork * 1_000_000 + block_number),
+            )
+        })
+        .collect()
+}
+
+///
+/// Reorg detection
+///
+/// purpose: detect blocks replaced on a node after they were seen, and how long nodes took
+///          to agree on the new blocks.
+/// success case: the depth of the reorg and the convergence time of each node are reported
+///               once both nodes are on the same fork.
+///
+#[rstest]
+fn work_reorg() {
+    let start = Instant::now();
+    let mut tracker = ReorgTracker::new();
+
+    assert_eq!(tracker.update(DEOXYS, &chain(10..=20, 0), start), None);
+    assert_eq!(tracker.update(PATHFINDER, &chain(10..=20, 0), start), None);
+    assert_eq!(tracker.converged(&[DEOXYS, PATHFINDER]), None);
+
+    // pathfinder replaces blocks 18 to 20 first
+    let detected = start + Duration::from_secs(5);
+    let mut fork = chain(10..=17, 0);
+    fork.extend(chain(18..=21, 1));
+    assert_eq!(tracker.update(PATHFINDER, &fork, detected), Some(18..=20));
+    assert_eq!(tracker.update(DEOXYS, &chain(10..=20, 0), detected), None);
+    assert!(tracker.is_reorganizing());
+    assert_eq!(tracker.converged(&[DEOXYS, PATHFINDER]), None);
+
+    // deoxys follows 10 seconds later
+    let followed = detected + Duration::from_secs(10);
+    assert_eq!(tracker.update(DEOXYS, &fork, followed), Some(18..=20));
+
+    let reorg = tracker
+        .converged(&[DEOXYS, PATHFINDER])
+        .expect("Nodes agree on the new blocks");
+    assert_eq!(reorg.start, 18);
+    assert_eq!(reorg.depth, 3);
+    assert_eq!(
+        reorg.convergence,
+        vec![
+            (DEOXYS.to_string(), Duration::from_secs(10)),
+            (PATHFINDER.to_string(), Duration::ZERO),
+        ]
+    );
+    assert!(!tracker.is_reorganizing());
+
+    tracker.prune(15);
+    assert_eq!(tracker.hash(DEOXYS, 14), None);
+    assert_eq!(tracker.hash(DEOXYS, 21), Some(fork[11].1));
+}
+
+///
+/// Reorg detection
+///
+/// purpose: detect a reorg which first shortens the chain of a node, then re-extends it
+///          with different blocks.
+/// success case: the blocks above the lowered tip are reported as replaced, and the reorg
+///               converges once both nodes have the new blocks.
+///
+#[rstest]
+fn work_shortened_reorg() {
+    let start = Instant::now();
+    let mut tracker = ReorgTracker::new();
+
+    tracker.update(DEOXYS, &chain(10..=20, 0), start);
+    tracker.update(PATHFINDER, &chain(10..=20, 0), start);
+
+    // deoxys drops blocks 19 and 20
+    let detected = start + Duration::from_secs(5);
+    assert_eq!(
+        tracker.update(DEOXYS, &chain(10..=18, 0), detected),
+        Some(19..=20)
+    );
+    assert!(tracker.is_reorganizing());
+    assert_eq!(tracker.converged(&[DEOXYS, PATHFINDER]), None);
+
+    // then both nodes build blocks 19 and 20 on another fork
+    let mut fork = chain(10..=18, 0);
+    fork.extend(chain(19..=20, 1));
+    let followed = detected + Duration::from_secs(10);
+    assert_eq!(tracker.update(DEOXYS, &fork, followed), None);
+    assert_eq!(tracker.converged(&[DEOXYS, PATHFINDER]), None);
+    assert_eq!(tracker.update(PATHFINDER, &fork, followed), Some(19..=20));
+
+    let reorg = tracker
+        .converged(&[DEOXYS, PATHFINDER])
+        .expect("Nodes agree on the new blocks");
+    assert_eq!(reorg.start, 19);
+    assert_eq!(reorg.depth, 2);
+}
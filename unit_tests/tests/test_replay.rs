@@ -0,0 +1,93 @@
+mod common;
+use common::*;
+
+use std::fs;
+
+use serde_json::json;
+use unit_tests::{
+    batch::{BatchClient, Request},
+    replay::{load_capture, replay, CaptureRecorder, CapturedCall, Compare, ReplayOptions},
+    transport::{RawResponse, RpcObserver},
+};
+
+fn get_block(block_number: u64) -> Request {
+    Request::new(
+        "starknet_getBlockWithTxHashes",
+        json!({ "block_id": { "block_number": block_number } }),
+    )
+}
+
+///
+/// Workload capture
+///
+/// purpose: write calls to a capture file and read them back.
+/// success case: calls are read in timestamp order, responses are optional.
+///
+#[rstest]
+fn work_capture_roundtrip() {
+    let path = std::env::temp_dir().join(format!("ditto-capture-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let recorder = CaptureRecorder::new(&path, true).expect("Failed to create capture file");
+    let response = RawResponse::Success {
+        result: json!(50000),
+    };
+    recorder
+        .observe("starknet_blockNumber", &json!([]), &response)
+        .expect("Failed to capture call");
+
+    // a call captured elsewhere, earlier and without its response
+    let line = r#"{"timestamp_ms":0,"method":"starknet_chainId","params":[]}"#;
+    let mut content = fs::read_to_string(&path).unwrap();
+    content.push_str(line);
+    content.push('\n');
+    fs::write(&path, content).unwrap();
+
+    let calls = load_capture(&path).expect("Failed to load capture");
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].request.method, "starknet_chainId");
+    assert_eq!(calls[0].response, None);
+    assert_eq!(calls[1].request.method, "starknet_blockNumber");
+    assert_eq!(calls[1].response, Some(response));
+}
+
+///
+/// Workload replay
+///
+/// purpose: replay calls captured from Pathfinder against Deoxys, comparing responses
+///          with the recorded ones then with Pathfinder directly.
+/// success case: every call is replayed and responses match.
+///
+#[require(block_min = 2, spec_version = "0.5.1", live = true)]
+#[rstest]
+#[tokio::test]
+async fn work_replay(batch_deoxys: BatchClient, batch_pathfinder: BatchClient) {
+    let mut calls = vec![];
+    for (i, block_number) in [0, 1, 2].into_iter().enumerate() {
+        let request = get_block(block_number);
+        let response = batch_pathfinder.call(&request).await.expect(ERR_PATHFINDER);
+        calls.push(CapturedCall {
+            timestamp_ms: i as u64 * 100,
+            request,
+            response: Some(response),
+        });
+    }
+
+    for compare in [Compare::Recorded, Compare::Reference(batch_pathfinder)] {
+        let options = ReplayOptions {
+            speed: 2.0,
+            compare,
+            ..Default::default()
+        };
+        let report = replay(&batch_deoxys, &calls, &options)
+            .await
+            .expect(ERR_DEOXYS);
+        println!("{report}");
+
+        assert_eq!(report.stats["starknet_getBlockWithTxHashes"].count(), 3);
+        assert_eq!(report.compared, 3);
+        assert!(report.mismatches.is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+mod common;
+use common::*;
+
+use unit_tests::sampling::Sampler;
+
+///
+/// Seeded block sampling
+///
+/// purpose: sampled blocks can be reproduced from the seed.
+/// success case: two samplers with the same seed pick the same blocks.
+///
+#[rstest]
+fn work_same_seed() {
+    let blocks = Sampler::with_seed(42).uniform(0..=600_000, 10);
+
+    assert_eq!(Sampler::with_seed(42).uniform(0..=600_000, 10), blocks);
+    assert!(blocks.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(blocks.iter().all(|block| *block <= 600_000));
+}
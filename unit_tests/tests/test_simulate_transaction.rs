@@ -8,7 +8,7 @@ use starknet_core::types::{
     SimulationFlag, StarknetError,
 };
 use starknet_core::utils::get_selector_from_name;
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
 use std::assert_matches::assert_matches;
 use std::convert::From;
 
@@ -48,7 +48,7 @@ use std::convert::From;
 
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_block(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_non_existing_block(deoxys: JsonRpcClient<DittoTransport>) {
     let ok_invoke_transaction = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
         max_fee: FieldElement::ZERO,
         signature: vec![],
@@ -77,7 +77,7 @@ async fn fail_non_existing_block(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn fail_max_fee_too_big(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_max_fee_too_big(deoxys: JsonRpcClient<DittoTransport>) {
     let max_fee_invoke_transaction = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
         max_fee: FieldElement::from_hex_be("0xffffffffffffffffff").unwrap(),
         signature: vec![
@@ -130,7 +130,7 @@ async fn fail_max_fee_too_big(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn fail_max_fee_too_low(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_max_fee_too_low(deoxys: JsonRpcClient<DittoTransport>) {
     let max_fee_invoke_transaction = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
         max_fee: FieldElement::from_hex_be("0xf").unwrap(),
         signature: vec![
@@ -183,7 +183,7 @@ async fn fail_max_fee_too_low(deoxys: JsonRpcClient<HttpTransport>) {
 
 #[rstest]
 #[tokio::test]
-async fn fail_if_one_txn_cannot_be_executed(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_if_one_txn_cannot_be_executed(deoxys: JsonRpcClient<DittoTransport>) {
     let ok_invoke_transaction = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
         max_fee: FieldElement::from_hex_be("0xffffffffffff").unwrap(),
         signature: vec![
@@ -262,8 +262,8 @@ async fn fail_if_one_txn_cannot_be_executed(deoxys: JsonRpcClient<HttpTransport>
 #[rstest]
 #[tokio::test]
 async fn works_ok_on_no_validate(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     let tx = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
         max_fee: FieldElement::from_hex_be("0xffffffffffff").unwrap(),
@@ -324,8 +324,8 @@ async fn works_ok_on_no_validate(
 #[rstest]
 #[tokio::test]
 async fn works_ok_on_validate_without_signature_with_skip_validate(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     let tx = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
         max_fee: FieldElement::from_hex_be("0xffffffffffff").unwrap(),
@@ -375,8 +375,8 @@ async fn works_ok_on_validate_without_signature_with_skip_validate(
 #[rstest]
 #[tokio::test]
 async fn works_ok_without_max_fee_with_skip_fee_charge(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
     let tx = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
         max_fee: FieldElement::from_hex_be("0x00").unwrap(),
@@ -0,0 +1,99 @@
+#![feature(assert_matches)]
+
+mod common;
+use common::*;
+
+use std::assert_matches::assert_matches;
+
+use serde_json::json;
+use starknet_core::types::{BlockId, FieldElement, StarknetError};
+use starknet_providers::{JsonRpcClient, Provider, ProviderError};
+use unit_tests::{
+    spec::{OpenRpcSpec, SpecValidator},
+    transport::{RawError, RawResponse},
+};
+
+///
+/// Spec validation of raw RPC responses
+///
+/// purpose: load and compile every supported spec version.
+/// success case: all methods of the api, trace and write specs are available.
+///
+#[rstest]
+#[case(SPEC_0_5_1)]
+#[case(SPEC_0_6_0)]
+fn work_load_spec(#[case] version: &str) {
+    let spec = OpenRpcSpec::load(version).expect("Failed to load spec");
+    SpecValidator::new(&spec).expect("Failed to compile spec");
+
+    assert!(spec.method("starknet_getBlockWithTxs").is_some());
+    assert!(spec.method("starknet_traceBlockTransactions").is_some());
+    assert!(spec.method("starknet_addInvokeTransaction").is_some());
+}
+
+///
+/// Spec validation of raw RPC responses
+///
+/// purpose: validate a result which does not match the method's result schema.
+/// fail case: violation is reported at the offending field.
+///
+#[rstest]
+fn fail_invalid_result() {
+    let spec = OpenRpcSpec::load(SPEC_0_5_1).expect("Failed to load spec");
+    let validator = SpecValidator::new(&spec).expect("Failed to compile spec");
+
+    let response = RawResponse::Success {
+        result: json!({ "block_hash": "0x1", "block_number": -1 }),
+    };
+    let violations = validator.validate("starknet_blockHashAndNumber", &response);
+
+    assert!(!violations.is_empty());
+    assert!(violations
+        .iter()
+        .all(|violation| violation.pointer == "/result/block_number"));
+}
+
+///
+/// Spec validation of raw RPC responses
+///
+/// purpose: validate an error which is not listed for the method.
+/// fail case: violation is reported on the error code.
+///
+#[rstest]
+fn fail_unlisted_error() {
+    let spec = OpenRpcSpec::load(SPEC_0_5_1).expect("Failed to load spec");
+    let validator = SpecValidator::new(&spec).expect("Failed to compile spec");
+
+    let response = RawResponse::Error {
+        error: RawError {
+            code: 24,
+            message: String::from("Block not found"),
+            data: None,
+        },
+    };
+    let violations = validator.validate("starknet_blockNumber", &response);
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].pointer, "/error/code");
+}
+
+///
+/// Spec validation of raw RPC responses
+///
+/// purpose: error responses from the node go through the validating transport.
+/// success case: `BLOCK_NOT_FOUND` is a valid error for `starknet_getBlockWithTxHashes`.
+///
+#[require(spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_error_response(deoxys: JsonRpcClient<DittoTransport>) {
+    let response_deoxys = deoxys
+        .get_block_with_tx_hashes(BlockId::Hash(FieldElement::ZERO))
+        .await
+        .err();
+
+    assert_matches!(
+        response_deoxys,
+        Some(ProviderError::StarknetError(StarknetError::BlockNotFound))
+    );
+}
@@ -2,7 +2,7 @@
 
 mod common;
 use common::*;
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet_providers::{JsonRpcClient, Provider};
 
 ///
 /// Unit test for `starknet_specversion`
@@ -13,7 +13,7 @@ use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
 #[rstest]
 #[tokio::test]
 #[logging]
-async fn test_specversion(deoxys: JsonRpcClient<HttpTransport>) {
+async fn test_specversion(deoxys: JsonRpcClient<DittoTransport>) {
     let response_deoxys = deoxys.spec_version().await.expect(ERR_DEOXYS);
 
     log::info!("Deoxys RPC spec: {}", response_deoxys);
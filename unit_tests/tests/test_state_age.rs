@@ -0,0 +1,81 @@
+mod common;
+use common::*;
+
+use std::{collections::BTreeMap, time::Duration};
+
+use starknet_providers::JsonRpcClient;
+use unit_tests::bench::{
+    state_age::{age_buckets, run_state_age, AgeReport, AgeWorkload},
+    Method, Stats,
+};
+
+fn stats(ms: u64) -> Stats {
+    let mut stats = Stats::new();
+    stats.record(Duration::from_millis(ms));
+    stats
+}
+
+///
+/// State access by block age
+///
+/// purpose: spread queries from a contract's deployment up to the tip, and find where
+///          latency jumps.
+/// success case: buckets include both ends, a bucket twice as slow as the next one is a
+///               cliff.
+///
+#[rstest]
+fn work_buckets_and_cliffs() {
+    assert_eq!(age_buckets(100, 1000, 4), vec![100, 400, 700, 1000]);
+    assert_eq!(age_buckets(100, 1000, 1), vec![1000]);
+    assert_eq!(age_buckets(1000, 1000, 3), vec![1000]);
+
+    let report = AgeReport {
+        tip: 1000,
+        blocks: vec![100, 400, 700, 1000],
+        results: BTreeMap::from([(
+            Method::GetStorageAt,
+            BTreeMap::from([(
+                String::from(DEOXYS),
+                vec![stats(50), stats(45), stats(10), stats(9)],
+            )]),
+        )]),
+    };
+    println!("{report}");
+
+    assert_eq!(report.cliffs(Method::GetStorageAt, DEOXYS), vec![400]);
+    assert!(report.cliffs(Method::GetNonce, DEOXYS).is_empty());
+}
+
+///
+/// State access by block age
+///
+/// purpose: query the same contract state at different block ages on each node.
+/// success case: every bucket has a measurement for every node, without errors.
+///
+#[require(block_min = 50_000, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_state_age(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let workload = AgeWorkload {
+        methods: vec![Method::GetStorageAt],
+        buckets: 3,
+        requests: 2,
+        ..Default::default()
+    };
+
+    let report = run_state_age(&[(DEOXYS, &deoxys), (PATHFINDER, &pathfinder)], &workload)
+        .await
+        .expect("Error while benchmarking state access");
+    println!("{report}");
+
+    for node in [DEOXYS, PATHFINDER] {
+        let buckets = &report.results[&Method::GetStorageAt][node];
+        assert_eq!(buckets.len(), report.blocks.len());
+        assert!(buckets
+            .iter()
+            .all(|stats| stats.count() == 2 && stats.errors == 0));
+    }
+}
@@ -0,0 +1,162 @@
+mod common;
+use common::*;
+
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use starknet_providers::JsonRpcClient;
+use unit_tests::{
+    batch::Request,
+    cassette::Cassette,
+    replay::CapturedCall,
+    sweep::{compare_block, sweep, Checkpoint, Component, SweepOptions},
+    transport::RawResponse,
+};
+
+fn checkpoint_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("ditto-sweep-{name}-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn state_update(storage_diffs: Value, nonces: Value) -> Value {
+    json!({
+        "block_hash": "0xa",
+        "new_root": "0x1",
+        "old_root": "0x2",
+        "state_diff": {
+            "storage_diffs": storage_diffs,
+            "deprecated_declared_classes": [],
+            "declared_classes": [],
+            "deployed_contracts": [],
+            "replaced_classes": [],
+            "nonces": nonces
+        }
+    })
+}
+
+/// Node answering `starknet_getStateUpdate` of block 1 with `state_update`
+fn node(state_update: Value) -> JsonRpcClient<DittoTransport> {
+    let call = CapturedCall {
+        timestamp_ms: 0,
+        request: Request::new(
+            "starknet_getStateUpdate",
+            json!({ "block_id": { "block_number": 1 } }),
+        ),
+        response: Some(RawResponse::Success {
+            result: state_update,
+        }),
+    };
+
+    JsonRpcClient::new(DittoTransport::replay(Cassette::new(vec![call])))
+}
+
+///
+/// Differential sweep over a range of blocks
+///
+/// purpose: compare blocks, receipts, state updates and traces between nodes.
+/// success case: no divergence in the first blocks of the chain.
+///
+#[require(block_min = 100, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_with_range(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let report = sweep(&deoxys, &pathfinder, 0..=100, &SweepOptions::default())
+        .await
+        .expect("Error while sweeping blocks");
+
+    println!("{report}");
+    assert!(report.is_done());
+    assert!(report.divergences.is_empty());
+}
+
+///
+/// Differential sweep resumed from a checkpoint
+///
+/// purpose: resume a sweep from its checkpoint file.
+/// success case: a finished sweep is saved to disk and resuming it returns the same report.
+///
+#[require(block_min = 100, spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_resume(
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+) {
+    let path = checkpoint_path("resume");
+    let options = SweepOptions {
+        components: vec![Component::Block],
+        checkpoint: Some(path.clone()),
+        ..Default::default()
+    };
+
+    let report = sweep(&deoxys, &pathfinder, 0..=100, &options)
+        .await
+        .expect("Error while sweeping blocks");
+    assert_eq!(Checkpoint::load(&path, &(0..=100)).unwrap(), report);
+
+    let resumed = sweep(&deoxys, &pathfinder, 0..=100, &options)
+        .await
+        .expect("Error while resuming sweep");
+    assert_eq!(resumed, report);
+}
+
+///
+/// Differential sweep checkpoint
+///
+/// purpose: refuse to resume a sweep over another range.
+/// success case: loading a checkpoint with a different range fails.
+///
+#[rstest]
+fn fail_checkpoint_range() {
+    let path = checkpoint_path("range");
+    Checkpoint::new(&(0..=10)).save(&path).unwrap();
+
+    assert!(Checkpoint::load(&path, &(0..=10)).is_ok());
+    assert!(Checkpoint::load(&path, &(0..=20)).is_err());
+}
+
+///
+/// Differential sweep of state updates
+///
+/// purpose: compare state updates listing the same diffs in a different order.
+/// success case: order is ignored, a different value is still reported.
+///
+#[rstest]
+#[tokio::test]
+async fn work_unordered_state_update() {
+    let storage = |value: &str| {
+        json!([
+            { "address": "0x1", "storage_entries": [{ "key": "0x1", "value": "0x1" }] },
+            { "address": "0x2", "storage_entries": [
+                { "key": "0x1", "value": "0x1" },
+                { "key": "0x2", "value": value }
+            ] }
+        ])
+    };
+    let nonces = json!([
+        { "contract_address": "0x1", "nonce": "0x1" },
+        { "contract_address": "0x2", "nonce": "0x2" }
+    ]);
+    let mut reversed = storage("0x2");
+    reversed.as_array_mut().unwrap().reverse();
+    reversed[0]["storage_entries"]
+        .as_array_mut()
+        .unwrap()
+        .reverse();
+    let mut reversed_nonces = nonces.clone();
+    reversed_nonces.as_array_mut().unwrap().reverse();
+
+    let deoxys = node(state_update(storage("0x2"), nonces.clone()));
+    let pathfinder = node(state_update(reversed, reversed_nonces));
+    let (_, divergences) = compare_block(&deoxys, &pathfinder, 1, &[Component::StateUpdate]).await;
+    assert!(divergences.is_empty(), "{divergences:?}");
+
+    let pathfinder = node(state_update(storage("0x3"), nonces));
+    let (_, divergences) = compare_block(&deoxys, &pathfinder, 1, &[Component::StateUpdate]).await;
+    assert_eq!(divergences.len(), 1);
+    assert_eq!(divergences[0].component, Component::StateUpdate);
+}
@@ -0,0 +1,78 @@
+mod common;
+use common::*;
+
+use std::time::Duration;
+
+use starknet_providers::JsonRpcClient;
+use unit_tests::bench::sync::{sample_sync, SyncProgress, SyncSample};
+
+fn sample(secs: u64, block_number: u64, highest_block: u64, transactions: u64) -> SyncSample {
+    SyncSample {
+        elapsed: Duration::from_secs(secs),
+        block_number,
+        highest_block,
+        transactions,
+    }
+}
+
+///
+/// Sync speed
+///
+/// purpose: compute sync speed and time to tip from samples of a node's progress.
+/// success case: time to tip accounts for the tip moving while the node syncs.
+///
+#[rstest]
+fn work_sync_progress() {
+    let progress = SyncProgress {
+        samples: vec![
+            sample(0, 1000, 2000, 0),
+            sample(10, 1100, 2000, 500),
+            sample(20, 1200, 2010, 1000),
+        ],
+    };
+
+    assert_eq!(progress.blocks_per_second(), 10.0);
+    assert_eq!(progress.transactions_per_second(), 50.0);
+    assert_eq!(progress.chain_blocks_per_second(), 0.5);
+    assert_eq!(
+        progress.time_to_tip(),
+        Some(Duration::from_secs_f64(810.0 / 9.5))
+    );
+
+    let stuck = SyncProgress {
+        samples: vec![sample(0, 1000, 2000, 0), sample(10, 1000, 2010, 0)],
+    };
+    assert_eq!(stuck.time_to_tip(), None);
+
+    let synced = SyncProgress {
+        samples: vec![sample(0, 2000, 2000, 0)],
+    };
+    assert_eq!(synced.time_to_tip(), Some(Duration::ZERO));
+}
+
+///
+/// Sync speed
+///
+/// purpose: sample the sync progress of a live node.
+/// success case: one sample per interval, with increasing block numbers.
+///
+#[require(block_min = "latest", spec_version = "0.5.1")]
+#[rstest]
+#[tokio::test]
+async fn work_sample_sync(deoxys: JsonRpcClient<DittoTransport>) {
+    let progress = sample_sync(
+        &deoxys,
+        Duration::from_secs(1),
+        Duration::from_secs(3),
+        true,
+    )
+    .await
+    .expect(ERR_DEOXYS);
+    println!("{progress}");
+
+    assert_eq!(progress.samples.len(), 4);
+    assert!(progress
+        .samples
+        .windows(2)
+        .all(|pair| pair[0].block_number <= pair[1].block_number));
+}
@@ -2,7 +2,7 @@ mod common;
 use common::*;
 
 use starknet_core::types::SyncStatusType;
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet_providers::{JsonRpcClient, Provider};
 use std::collections::HashMap;
 
 ///
@@ -14,7 +14,7 @@ use std::collections::HashMap;
 #[require(block_min = "latest", spec_version = "0.5.1")]
 #[rstest]
 #[tokio::test]
-async fn syncing(clients: HashMap<String, JsonRpcClient<HttpTransport>>) {
+async fn syncing(clients: HashMap<String, JsonRpcClient<DittoTransport>>) {
     let deoxys = &clients[DEOXYS];
     let pathfinder = &clients[PATHFINDER];
 
@@ -0,0 +1,91 @@
+mod common;
+use common::*;
+
+use std::{collections::HashMap, time::Duration};
+
+use serde_json::{json, Value};
+use starknet_core::types::FieldElement;
+use starknet_providers::{JsonRpcClient, Provider};
+use unit_tests::{
+    batch::Request,
+    cassette::Cassette,
+    replay::CapturedCall,
+    tip::{latest, TipOptions},
+    transport::RawResponse,
+};
+
+fn call(method: &str, params: Value, result: Value) -> CapturedCall {
+    CapturedCall {
+        timestamp_ms: 0,
+        request: Request::new(method, params),
+        response: Some(RawResponse::Success { result }),
+    }
+}
+
+///
+/// Node at block `tip`, which has block 9 of hash 0xa with a single transaction.
+///
+fn node(tip: u64) -> JsonRpcClient<DittoTransport> {
+    let block = json!({
+        "status": "ACCEPTED_ON_L2",
+        "block_hash": "0xa",
+        "parent_hash": "0x1",
+        "block_number": 9,
+        "new_root": "0x1",
+        "timestamp": 1700000000,
+        "sequencer_address": "0x1",
+        "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+        "starknet_version": "0.12.3",
+        "transactions": ["0x2"]
+    });
+    let calls = vec![
+        call("starknet_blockNumber", json!([]), json!(tip)),
+        call(
+            "starknet_getBlockWithTxHashes",
+            json!({ "block_id": { "block_number": 9 } }),
+            block,
+        ),
+        call(
+            "starknet_getBlockTransactionCount",
+            json!({ "block_id": { "block_hash": "0xa" } }),
+            json!(1),
+        ),
+    ];
+
+    JsonRpcClient::new(DittoTransport::replay(Cassette::new(calls)))
+}
+
+///
+/// Latest block on nodes at different tips
+///
+/// purpose: query the latest block while one node lags behind the other.
+/// success case: nodes are queried on their common block, and the lag is reported rather
+///               than failing.
+///
+#[rstest]
+#[tokio::test]
+async fn work_lagging_node() {
+    let clients = HashMap::from([
+        (String::from(DEOXYS), node(10)),
+        (String::from(PATHFINDER), node(9)),
+    ]);
+    let options = TipOptions {
+        budget: Duration::ZERO,
+        ..Default::default()
+    };
+
+    let (tip, responses) = latest(&clients, &options, |client, block_id| {
+        client.get_block_transaction_count(block_id)
+    })
+    .await
+    .expect("Error waiting for response from nodes");
+
+    assert_eq!(tip.block_number, 9);
+    assert_eq!(tip.block_hash, FieldElement::from(10u64));
+    assert_eq!(
+        tip.lag,
+        vec![(String::from(DEOXYS), 1), (String::from(PATHFINDER), 0)]
+    );
+    assert_eq!(responses[DEOXYS], 1);
+    assert_eq!(responses[PATHFINDER], 1);
+}
@@ -3,18 +3,41 @@
 mod common;
 use common::*;
 
-use rand::Rng;
 use std::assert_matches::assert_matches;
 
 use starknet_core::types::{BlockId, FieldElement, StarknetError};
-use starknet_providers::{
-    jsonrpc::{HttpTransport, JsonRpcClient},
-    Provider, ProviderError,
+use starknet_providers::{jsonrpc::JsonRpcClient, Provider, ProviderError};
+use unit_tests::{
+    sampling::{Sampler, Strategy},
+    trace::TraceComparator,
 };
 
+async fn assert_same_traces(
+    deoxys: &JsonRpcClient<DittoTransport>,
+    pathfinder: &JsonRpcClient<DittoTransport>,
+    block_id: BlockId,
+) {
+    let deoxys_trace = deoxys
+        .trace_block_transactions(block_id)
+        .await
+        .expect(ERR_DEOXYS);
+    let pathfinder_trace = pathfinder
+        .trace_block_transactions(block_id)
+        .await
+        .expect(ERR_PATHFINDER);
+
+    let difference = TraceComparator::default()
+        .compare_block(&deoxys_trace, &pathfinder_trace)
+        .expect("Error while serializing traces");
+
+    if let Some(difference) = difference {
+        panic!("Traces differ at {block_id:?}: {difference}");
+    }
+}
+
 #[rstest]
 #[tokio::test]
-async fn fail_non_existing_block(deoxys: JsonRpcClient<HttpTransport>) {
+async fn fail_non_existing_block(deoxys: JsonRpcClient<DittoTransport>) {
     assert_matches!(
         deoxys
             .trace_block_transactions(BlockId::Hash(FieldElement::ZERO))
@@ -26,46 +49,35 @@ async fn fail_non_existing_block(deoxys: JsonRpcClient<HttpTransport>) {
 #[rstest]
 #[tokio::test]
 async fn works_ok_for_block_10000(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
-    let block_number = BlockId::Number(10000);
-
-    let deoxys_trace = deoxys.trace_block_transactions(block_number).await;
-    let _pathfinder_trace = pathfinder.trace_block_transactions(block_number).await;
-
-    assert_matches!(deoxys_trace, _pathfinder_trace);
+    assert_same_traces(&deoxys, &pathfinder, BlockId::Number(10000)).await;
 }
 
 #[rstest]
 #[tokio::test]
 async fn works_ok_for_block_300000(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
 ) {
-    let block_number = BlockId::Number(300000);
-
-    let deoxys_trace = deoxys.trace_block_transactions(block_number).await;
-    let _pathfinder_trace = pathfinder.trace_block_transactions(block_number).await;
-
-    assert_matches!(deoxys_trace, _pathfinder_trace);
+    assert_same_traces(&deoxys, &pathfinder, BlockId::Number(300000)).await;
 }
 
 #[rstest]
 #[tokio::test]
+#[logging]
 async fn works_ok_for_random_block(
-    deoxys: JsonRpcClient<HttpTransport>,
-    pathfinder: JsonRpcClient<HttpTransport>,
+    deoxys: JsonRpcClient<DittoTransport>,
+    pathfinder: JsonRpcClient<DittoTransport>,
+    mut sampler: Sampler,
 ) {
-    let mut rng = rand::thread_rng();
-    let random_block_number = rng.gen_range(100000..602000);
-
-    let block_number = BlockId::Number(random_block_number);
-
-    let deoxys_trace = deoxys.trace_block_transactions(block_number).await;
-    let _pathfinder_trace = pathfinder.trace_block_transactions(block_number).await;
-    println!("{:?}", deoxys_trace);
-    println!("block choose is: {:?}", block_number);
+    let blocks = sampler
+        .sample(&deoxys, Strategy::Uniform, 1)
+        .await
+        .expect(ERR_DEOXYS);
 
-    assert_matches!(deoxys_trace, _pathfinder_trace);
+    for block_number in blocks {
+        assert_same_traces(&deoxys, &pathfinder, BlockId::Number(block_number)).await;
+    }
 }
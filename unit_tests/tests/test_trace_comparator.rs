@@ -0,0 +1,90 @@
+mod common;
+use common::*;
+
+use serde_json::{json, Value};
+use unit_tests::trace::{Tolerance, TraceComparator};
+
+fn trace(value: u64, steps: u64, storage_keys: [&str; 2]) -> Value {
+    json!({
+        "type": "INVOKE",
+        "validate_invocation": {
+            "contract_address": "0x1",
+            "calldata": [],
+            "events": [],
+            "calls": []
+        },
+        "execute_invocation": {
+            "contract_address": "0x1",
+            "calldata": ["0x2"],
+            "events": [],
+            "execution_resources": { "steps": steps },
+            "calls": [{
+                "contract_address": "0x2",
+                "calldata": [],
+                "events": [],
+                "calls": [],
+            }, {
+                "contract_address": "0x3",
+                "calldata": [],
+                "events": [{ "keys": ["0x4"], "data": [value] }],
+                "calls": [],
+            }]
+        },
+        "state_diff": {
+            "storage_diffs": [{
+                "address": "0x3",
+                "storage_entries": [
+                    { "key": storage_keys[0], "value": "0x1" },
+                    { "key": storage_keys[1], "value": "0x1" }
+                ]
+            }]
+        }
+    })
+}
+
+///
+/// Trace comparator
+///
+/// purpose: report the first differing call of two traces.
+/// success case: the call path leads to the nested call whose event differs.
+///
+#[rstest]
+fn work_call_path() {
+    let difference = TraceComparator::default()
+        .compare_trace(
+            &trace(1, 100, ["0x5", "0x6"]),
+            &trace(2, 100, ["0x5", "0x6"]),
+        )
+        .expect("Traces should differ");
+
+    assert_eq!(difference.call_path, vec!["execute_invocation", "calls[1]"]);
+    assert_eq!(difference.difference.pointer, "/events/0/data/0");
+}
+
+///
+/// Trace comparator
+///
+/// purpose: apply per-field tolerance rules.
+/// success case: state diffs are compared as sets and execution resources within tolerance.
+///
+#[rstest]
+fn work_tolerance() {
+    let left = trace(1, 100, ["0x5", "0x6"]);
+    let right = trace(1, 104, ["0x6", "0x5"]);
+
+    let difference = TraceComparator::default()
+        .compare_trace(&left, &right)
+        .expect("Execution resources should differ");
+    assert_eq!(difference.difference.pointer, "/execution_resources/steps");
+
+    let comparator =
+        TraceComparator::default().with_rule("execution_resources", Tolerance::Relative(0.05));
+    assert_eq!(comparator.compare_trace(&left, &right), None);
+
+    let comparator = TraceComparator::new().with_rule("execution_resources", Tolerance::Ignore);
+    let difference = comparator
+        .compare_trace(&left, &right)
+        .expect("State diffs should differ when ordered");
+    assert!(difference.call_path.is_empty());
+    assert!(difference.difference.pointer.starts_with("/state_diff"));
+}